@@ -1,11 +1,19 @@
-use chrono::Local;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use crate::daemon::{DaemonEvent, DaemonStatus, Request};
 use crate::garden::Garden;
 use crate::input::InputAction;
-use crate::plant::Plant;
-use crate::storage::{load_data, save_data, Data, Settings, Statistics};
-use crate::theme::{Theme, ThemeVariant};
-use crate::timer::{SessionType, Timer};
+use crate::plant::{GrowthMode, Plant};
+use crate::history::History;
+use crate::storage::{Data, Settings, Storage};
+use crate::theme::{Theme, Themes};
+use crate::timer::{SessionType, Timer, TimerState};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
 use ratatui::widgets::{ListState, ScrollbarState};
+use std::time::{Duration, Instant};
+
+// Index of the last row on the Settings tab: Durations, Theme, Cycle (4), Growth (6), Next seed, Strict mode (3), Goals (4)
+const LAST_SETTING: usize = 21;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tab {
@@ -13,6 +21,7 @@ pub enum Tab {
     Plant,
     Stats,
     Settings,
+    Garden,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,6 +30,14 @@ pub enum Focus {
     Right,
 }
 
+// Drawn over the current tab and takes every key while open
+#[derive(Debug, Clone, PartialEq)]
+pub enum Overlay {
+    Help,
+    Palette(crate::palette::Palette),
+    Tasks(crate::tasks::TaskPicker),
+}
+
 #[derive(Debug)]
 pub struct App {
     pub tab: Tab,
@@ -28,163 +45,386 @@ pub struct App {
     pub plant: Plant,
     pub garden: Garden,
     pub settings: Settings,
-    pub statistics: Statistics,
+    pub history: History,
+    pub tasks: crate::tasks::Tasks,
+    pub storage: Box<dyn Storage>,
     pub theme: Theme,
+    pub themes: Themes,
     // UI state
     pub timer_selected_session: usize,
     pub timer_selected_auto: usize,
     pub settings_selected: usize,
     pub stats_selected: usize,
+    pub stats_period: crate::history::Period,
+    pub stats_offset: u32, // periods back from the current one
+    pub heatmap_cursor: NaiveDate,
+    pub garden_selected: usize,
+    pub garden_month: Option<(i32, u32)>, // (year, month), None shows every plant
+    pub garden_columns: usize, // set while drawing, used to move between rows
     pub focus: Focus,
+    pub overlay: Option<Overlay>,
     pub should_quit: bool,
+    pub status_message: Option<String>, // shown in the footer until the next key press
+    pub daemon: Option<crate::daemon::DaemonClient>, // set while a daemon owns the timer
+    pub data_lock: Option<std::fs::File>, // held while this window saves the data itself
+    pub read_only: bool, // set by App::read_only
+    saved: Option<(serde_json::Value, usize, usize)>, // fingerprint of the last save
+    pub events: Vec<crate::events::AppEvent>, // handed to the hooks on the next tick
+    pub hooks: crate::hooks::Hooks,
+    pub notifiers: crate::notify::Notifiers,
+    seen_start: Option<DateTime<Local>>, // start of the last session reported as started
+    last_streak: u32,
+    streak_day: NaiveDate, // streaks are recomputed when the date changes
     pub timer_auto_list_state: ListState,
     pub timer_auto_scrollbar_state: ScrollbarState,
+    pub settings_list_state: ListState, // scrolls the settings list on short terminals
+    pub hit_areas: crate::ui::HitAreas, // set while drawing, used to route clicks
+    last_click: Option<(Instant, u16, u16)>,
 }
 
 impl App {
     pub fn new() -> Self {
-        let data = load_data();
+        App::open(false)
+    }
+
+    // Catches up in memory only, for commands that just show the data. Nothing is
+    // saved until `read_only` is cleared.
+    pub fn read_only() -> Self {
+        App::open(true)
+    }
+
+    fn open(read_only: bool) -> Self {
+        let daemon = crate::daemon::DaemonClient::connect();
+        let (storage, open_notice) = crate::storage::open();
+        App::with_storage(storage, open_notice, daemon, read_only)
+    }
+
+    fn with_storage(mut storage: Box<dyn Storage>, open_notice: Option<String>, daemon: Option<crate::daemon::DaemonClient>, read_only: bool) -> Self {
+        let (data, load_notice) = storage.load();
         let settings = data.settings.clone();
         let mut timer = Timer::new(&settings);
-        timer.auto_run = data.auto_run;
+        timer.auto_run = data.auto_run.clone();
         timer.auto_run_index = data.auto_run_index;
-        let plant = Plant::from_stage(data.current_plant_stage, data.growth_points);
+        timer.cycle_completed = data.cycle_completed;
+        if let Some(active) = &data.active_session {
+            timer.restore(active);
+        }
+        let plant = saved_plant(&data);
         let mut garden = Garden::new();
         garden.completed_plants = vec![]; // TODO: load from data if needed
-        garden.current_streak = data.statistics.current_streak;
-        garden.longest_streak = data.statistics.longest_streak;
-        garden.current_streak_start_date = data.statistics.current_streak_start_date;
-        garden.longest_streak_end_date = data.statistics.longest_streak_end_date;
-        garden.current_streak_dates = data.statistics.current_streak_dates.clone();
-        garden.longest_streak_dates = data.statistics.longest_streak_dates.clone();
         garden.completed_plants = data.completed_plants.clone();
-        garden.update_streaks(&data.statistics.recent_sessions);
-        let statistics = data.statistics;
-        let theme = Theme::new(settings.theme);
+        let history = History::new(data.sessions);
+        garden.update_streaks(&settings.goals.streak_days(&history));
+        let (themes, theme_problems) = Themes::load();
+        let theme = themes.theme(&settings.theme);
+        let theme_notice = match themes.find(&settings.theme) {
+            _ if !theme_problems.is_empty() => Some(theme_problems.join("; ")),
+            None => Some(format!("Theme `{}` wasn't found, using System", settings.theme)),
+            Some(_) => None,
+        };
+        // A session restored from the last run has had its start reported already
+        let seen_start = timer.started_at;
 
-        App {
+        let mut app = App {
             tab: Tab::Timer,
             timer,
             plant,
             garden,
             settings,
-            statistics,
+            history,
+            tasks: data.tasks.clone(),
+            storage,
             theme,
+            themes,
              timer_selected_session: 0,
              timer_selected_auto: 0,
              settings_selected: 0,
              stats_selected: 0,
+             stats_period: crate::history::Period::Day,
+             stats_offset: 0,
+             heatmap_cursor: Local::now().date_naive(),
+             garden_selected: 0,
+             garden_month: None,
+             garden_columns: 1,
              focus: Focus::Left,
+             overlay: None,
              should_quit: false,
+             status_message: load_notice.or(open_notice).or(theme_notice),
+             daemon,
+             data_lock: None,
+             read_only,
+             saved: None,
+             events: vec![],
+             hooks: crate::hooks::Hooks::new(),
+             notifiers: crate::notify::Notifiers::standard(),
+             seen_start,
+             last_streak: data.last_streak,
+             streak_day: Local::now().date_naive(),
              timer_auto_list_state: ListState::default(),
              timer_auto_scrollbar_state: ScrollbarState::new(1000),
+             settings_list_state: ListState::default(),
+             hit_areas: crate::ui::HitAreas::default(),
+             last_click: None,
+        };
+        match app.daemon.as_ref().map(|d| d.last.clone()) {
+            Some(status) => app.mirror(status),
+            // Credit the time that passed while the app was closed
+            None => app.tick(),
         }
+        app
     }
 
     pub fn tick(&mut self) {
-        if self.timer.tick() {
-            // Session finished
-            self.plant.add_growth();
-            self.statistics.total_sessions += 1;
-            let minutes = self.timer.session_type.duration_minutes(&self.settings);
-            self.statistics.total_minutes += minutes;
-            let today = Local::now();
-            // Update recent_sessions
-            if let Some((_, count)) = self.statistics.recent_sessions.iter_mut().find(|(d, _)| d.date_naive() == today.date_naive()) {
-                *count += 1;
-            } else {
-                self.statistics.recent_sessions.push((today, 1));
-            }
-            // Update recent_minutes
-            if let Some((_, mins)) = self.statistics.recent_minutes.iter_mut().find(|(d, _)| d.date_naive() == today.date_naive()) {
-                *mins += minutes;
-            } else {
-                self.statistics.recent_minutes.push((today, minutes));
-            }
-            match self.timer.session_type {
-                crate::timer::SessionType::Focus => {
-                    self.statistics.total_focus_sessions += 1;
-                    self.statistics.total_focus_minutes += minutes;
-                    // Update recent_focus_sessions
-                    if let Some((_, count)) = self.statistics.recent_focus_sessions.iter_mut().find(|(d, _)| d.date_naive() == today.date_naive()) {
-                        *count += 1;
-                    } else {
-                        self.statistics.recent_focus_sessions.push((today, 1));
-                    }
-                    // Update recent_focus_minutes
-                    if let Some((_, mins)) = self.statistics.recent_focus_minutes.iter_mut().find(|(d, _)| d.date_naive() == today.date_naive()) {
-                        *mins += minutes;
-                    } else {
-                        self.statistics.recent_focus_minutes.push((today, minutes));
-                    }
-                }
-                _ => {
-                    self.statistics.total_break_sessions += 1;
-                    self.statistics.total_break_minutes += minutes;
-                    // Update recent_break_sessions
-                    if let Some((_, count)) = self.statistics.recent_break_sessions.iter_mut().find(|(d, _)| d.date_naive() == today.date_naive()) {
-                        *count += 1;
-                    } else {
-                        self.statistics.recent_break_sessions.push((today, 1));
-                    }
-                    // Update recent_break_minutes
-                    if let Some((_, mins)) = self.statistics.recent_break_minutes.iter_mut().find(|(d, _)| d.date_naive() == today.date_naive()) {
-                        *mins += minutes;
-                    } else {
-                        self.statistics.recent_break_minutes.push((today, minutes));
-                    }
+        if let Some(daemon) = &self.daemon {
+            for event in daemon.events() {
+                match event {
+                    DaemonEvent::Status(status) => self.mirror(*status),
+                    DaemonEvent::App(event) => self.events.push(event),
+                    DaemonEvent::Closed => self.detach_daemon(),
                 }
             }
-            if self.plant.is_complete() {
-                self.garden.add_completed_plant(self.plant.clone());
-                self.plant = Plant::new();
-                self.statistics.completed_plants += 1;
-                // Update recent_plants
-                if let Some((_, count)) = self.statistics.recent_plants.iter_mut().find(|(d, _)| d.date_naive() == today.date_naive()) {
-                    *count += 1;
-                } else {
-                    self.statistics.recent_plants.push((today, 1));
-                }
+            return;
+        }
+        self.note_session_start();
+        // Loops so that a long gap finishes every session that fits into it
+        while self.timer.tick() {
+            let end_time = self.timer.deadline().unwrap_or_else(Local::now);
+            self.complete_session(end_time);
+            self.note_session_start();
+            self.save();
+        }
+        self.note_streak();
+    }
+
+    fn note_session_start(&mut self) {
+        if matches!(self.timer.state, TimerState::Running | TimerState::Paused) && self.timer.started_at != self.seen_start {
+            if let Some(started_at) = self.timer.started_at {
+                self.events.push(crate::events::AppEvent::SessionStart {
+                    session_type: self.timer.session_type,
+                    duration_minutes: self.timer.duration_seconds / 60,
+                    started_at,
+                });
             }
-            self.garden.update_streaks(&self.statistics.recent_sessions);
-            self.statistics.session_log.push(crate::storage::SessionLog {
-                session_type: self.timer.session_type,
-                duration: minutes,
-                end_time: Local::now(),
+        }
+        self.seen_start = self.timer.started_at;
+    }
+
+    // A streak only breaks by a day going by, so this runs on every tick rather than after sessions
+    fn note_streak(&mut self) {
+        let today = Local::now().date_naive();
+        if today != self.streak_day {
+            self.streak_day = today;
+            self.garden.update_streaks(&self.settings.goals.streak_days(&self.history));
+        }
+        let streak = self.garden.current_streak;
+        if streak < self.last_streak {
+            self.events.push(crate::events::AppEvent::StreakBroken {
+                streak_days: self.last_streak,
+                // The last day of the old streak, not the first of a new one
+                last_active_day: self.settings.goals.streak_days(&self.history).into_iter().rev().find(|day| !self.garden.current_streak_dates.contains(day)),
             });
-            if self.statistics.session_log.len() > 100 {
-                self.statistics.session_log.remove(0);
-            }
-            // Auto run next
-            if let Some(idx) = self.timer.auto_run_index {
-                if idx + 1 < self.timer.auto_run.len() {
-                    self.timer.auto_run_index = Some(idx + 1);
-                    self.timer.switch_session(self.timer.auto_run[idx + 1], &self.settings);
-                    self.timer.start();
-                } else {
-                    self.timer.auto_run_index = None;
-                }
+        }
+        if streak != self.last_streak {
+            self.last_streak = streak;
+            self.save();
+        }
+    }
+
+    // Runs hooks and notifications for everything queued since the last call and
+    // returns the events handled. Errors show up in the footer.
+    pub fn dispatch_events(&mut self) -> Vec<crate::events::AppEvent> {
+        let events = std::mem::take(&mut self.events);
+        // Events from the daemon have had their hooks and desktop notifications there
+        self.notifiers.desktop = self.daemon.is_none();
+        let mut errors = vec![];
+        for event in &events {
+            if self.daemon.is_none() {
+                self.hooks.run(&self.settings.hooks, event);
+            }
+            errors.extend(self.notifiers.notify(&self.settings.notifications, event));
+        }
+        errors.extend(self.hooks.errors());
+        if !errors.is_empty() {
+            self.status_message = Some(errors.join("; "));
+        }
+        events
+    }
+
+    // Called before exiting, so hooks of the last few events still get to run
+    pub fn finish_events(&mut self) {
+        if self.daemon.is_none() {
+            self.note_session_start();
+        }
+        self.dispatch_events();
+        self.hooks.wait();
+        let errors = self.hooks.errors();
+        if !errors.is_empty() {
+            self.status_message = Some(errors.join("; "));
+        }
+    }
+
+    fn complete_session(&mut self, end_time: DateTime<Local>) {
+        let minutes = self.timer.duration_seconds / 60;
+        let points = self.settings.growth.points_for(self.timer.session_type, minutes);
+        if points > 0 {
+            self.plant.add_growth(points);
+        }
+        if self.plant.is_complete() {
+            self.garden.add_completed_plant(self.plant.clone(), end_time.with_timezone(&Utc));
+            self.events.push(crate::events::AppEvent::PlantComplete {
+                species: self.plant.species.clone(),
+                species_name: crate::species::find(&self.plant.species).name.to_string(),
+                sessions: self.plant.sessions,
+                completed_at: end_time,
+            });
+            self.plant = Plant::new(self.next_species());
+        }
+        self.history.record(crate::storage::SessionLog {
+            session_type: self.timer.session_type,
+            duration: minutes,
+            end_time,
+            outcome: crate::storage::SessionOutcome::Completed,
+            task: self.session_task(),
+        });
+        self.events.push(crate::events::AppEvent::SessionComplete {
+            session_type: self.timer.session_type,
+            duration_minutes: minutes,
+            end_time,
+        });
+        self.garden.update_streaks(&self.settings.goals.streak_days(&self.history));
+        // The cycle keeps counting while an auto-run queue drives the timer
+        let next_in_cycle = self.settings.cycle_mode.then(|| self.timer.advance_cycle(self.timer.session_type, &self.settings));
+        // Auto run next
+        if let Some(idx) = self.timer.auto_run_index {
+            if idx + 1 < self.timer.auto_run.len() {
+                self.timer.auto_run_index = Some(idx + 1);
+                self.timer.switch_session(self.timer.auto_run[idx + 1], &self.settings);
+                self.timer.start_at(end_time);
+            } else {
+                self.timer.auto_run_index = None;
+            }
+        } else if let Some(next) = next_in_cycle {
+            self.timer.switch_session(next, &self.settings);
+            let auto_start = match next {
+                SessionType::Focus => self.settings.auto_start_focus,
+                _ => self.settings.auto_start_breaks,
+            };
+            if auto_start {
+                self.timer.start_at(end_time);
             }
         }
     }
 
+    // Records a session that is being cut short
+    fn abandon_session(&mut self) {
+        let in_progress = matches!(self.timer.state, crate::timer::TimerState::Running | crate::timer::TimerState::Paused | crate::timer::TimerState::Suspended);
+        if !in_progress {
+            return;
+        }
+        let now = Local::now();
+        // A start that no tick has seen yet still gets its hook, ahead of the abandon
+        self.note_session_start();
+        let elapsed_minutes = self.timer.elapsed_seconds(now) / 60;
+        self.history.record(crate::storage::SessionLog {
+            session_type: self.timer.session_type,
+            duration: elapsed_minutes,
+            end_time: now,
+            outcome: crate::storage::SessionOutcome::Abandoned,
+            task: self.session_task(),
+        });
+        self.events.push(crate::events::AppEvent::SessionAbandoned {
+            session_type: self.timer.session_type,
+            duration_minutes: self.timer.duration_seconds / 60,
+            elapsed_minutes,
+            end_time: now,
+        });
+    }
+
     pub fn handle_input(&mut self, action: InputAction) {
+        // A suspend gap has to be resolved before anything else touches the timer
+        if self.timer.state == crate::timer::TimerState::Suspended
+            && !matches!(action, InputAction::CountGap | InputAction::DiscardGap | InputAction::EndSession | InputAction::Help | InputAction::Quit)
+        {
+            return;
+        }
+        if self.daemon.is_some() {
+            if let Some(request) = self.daemon_request(action) {
+                self.send_to_daemon(request);
+                return;
+            }
+        }
         match action {
             InputAction::Tab(n) => {
                 match n {
                     1 => self.tab = Tab::Timer,
                     2 => self.tab = Tab::Plant,
-                    3 => self.tab = Tab::Stats,
+                    3 => {
+                        self.tab = Tab::Stats;
+                        self.focus = Focus::Left; // the heatmap cursor starts off
+                    }
                     4 => self.tab = Tab::Settings,
+                    5 => self.tab = Tab::Garden,
                     _ => {}
                 }
             }
+            InputAction::Left if self.tab == Tab::Garden => {
+                self.garden_selected = self.garden_selected.saturating_sub(1);
+            }
+            InputAction::Right if self.tab == Tab::Garden => {
+                if self.garden_selected + 1 < self.garden_plants().len() {
+                    self.garden_selected += 1;
+                }
+            }
+            InputAction::Left | InputAction::Right | InputAction::Up | InputAction::Down if self.in_heatmap() => {
+                let days = match action {
+                    InputAction::Left => -7,
+                    InputAction::Right => 7,
+                    InputAction::Up => -1,
+                    _ => 1,
+                };
+                let today = Local::now().date_naive();
+                let moved = self.heatmap_cursor + chrono::Duration::days(days);
+                self.heatmap_cursor = moved.clamp(crate::ui::heatmap_ui::first_day(today), today);
+            }
+            InputAction::Left if self.tab == Tab::Stats => {
+                if (self.stats_offset as i64) + 1 < self.stats_period_count() {
+                    self.stats_offset += 1;
+                }
+            }
+            InputAction::Right if self.tab == Tab::Stats => {
+                self.stats_offset = self.stats_offset.saturating_sub(1);
+            }
             InputAction::Left => {
                 self.focus = Focus::Left;
             }
             InputAction::Right => {
                 self.focus = Focus::Right;
             }
+            InputAction::PrevPeriod | InputAction::NextPeriod => {
+                if self.tab == Tab::Garden {
+                    // Months with plants in chronological order, followed by "all"
+                    let mut options: Vec<Option<(i32, u32)>> = self.garden_months().into_iter().map(Some).collect();
+                    options.push(None);
+                    let current = options.iter().position(|m| *m == self.garden_month).unwrap_or(options.len() - 1);
+                    let new_index = match action {
+                        InputAction::PrevPeriod => current.saturating_sub(1),
+                        _ => (current + 1).min(options.len() - 1),
+                    };
+                    self.garden_month = options[new_index];
+                    self.garden_selected = 0;
+                } else if self.tab == Tab::Stats {
+                    let periods = crate::history::Period::ALL;
+                    let current = periods.iter().position(|p| *p == self.stats_period).unwrap_or(0);
+                    let new_index = match action {
+                        InputAction::PrevPeriod => current.saturating_sub(1),
+                        _ => (current + 1).min(periods.len() - 1),
+                    };
+                    // Keeps showing the same stretch of time, e.g. the week holding the selected day
+                    let shown = self.stats_period_start();
+                    self.stats_period = periods[new_index];
+                    self.stats_offset = (self.stats_period.count(shown, Local::now().date_naive()) - 1).max(0) as u32;
+                }
+            }
             InputAction::Up => {
                 self.handle_up();
             }
@@ -203,6 +443,7 @@ impl App {
                             _ => {}
                         }
                     } else {
+                        self.abandon_session();
                         self.timer.switch_session(selected_session, &self.settings);
                         self.timer.start();
                     }
@@ -221,6 +462,7 @@ impl App {
                              _ => {}
                          }
                      } else {
+                         self.abandon_session();
                          self.timer.switch_session(selected_session, &self.settings);
                          self.timer.start();
                          self.timer.auto_run_index = Some(self.timer_selected_auto);
@@ -231,10 +473,15 @@ impl App {
             }
             InputAction::Stop => {
                 if self.tab == Tab::Timer {
-                    self.timer.stop();
+                    self.stop_session();
                 }
             }
             InputAction::Quit => self.should_quit = true,
+             InputAction::Enter if self.tab == Tab::Stats && self.stats_selected == 9 => {
+                // Hands the arrows to the heatmap cursor and back
+                self.focus = if self.in_heatmap() { Focus::Left } else { Focus::Right };
+                self.heatmap_cursor = self.heatmap_cursor.min(Local::now().date_naive());
+            }
              InputAction::Enter => {
                  if self.tab == Tab::Timer && self.focus == Focus::Left {
                      let sessions = [SessionType::Focus, SessionType::ShortBreak, SessionType::LongBreak];
@@ -245,6 +492,36 @@ impl App {
                      }
                  }
              }
+             InputAction::Reset => {
+                if self.tab == Tab::Timer {
+                    self.reset_session();
+                }
+            }
+            InputAction::Help => self.overlay = Some(Overlay::Help),
+            InputAction::Palette => self.overlay = Some(Overlay::Palette(crate::palette::Palette::default())),
+            InputAction::Tasks => self.overlay = Some(Overlay::Tasks(crate::tasks::TaskPicker::default())),
+            InputAction::TogglePause => self.toggle_pause(),
+            InputAction::StartSession(session_type) => self.start_session(session_type),
+            InputAction::ClearQueue => {
+                self.timer.auto_run.clear();
+                self.timer.auto_run_index = None;
+                self.timer_selected_auto = 0;
+                self.timer_auto_list_state.select(Some(0));
+            }
+            InputAction::SetTheme(index) => {
+                if let Some(named) = self.themes.list.get(index) {
+                    self.settings.theme = named.name.clone();
+                    self.theme = named.theme;
+                }
+            }
+            InputAction::Export(format) => self.export_stats(format),
+            InputAction::CountGap => self.timer.count_gap(),
+             InputAction::DiscardGap => self.timer.discard_gap(),
+             InputAction::EndSession => {
+                 if self.timer.state == crate::timer::TimerState::Suspended {
+                     self.stop_session();
+                 }
+             }
              InputAction::Delete => {
                  if self.tab == Tab::Timer && self.focus == Focus::Right && !self.timer.auto_run.is_empty() && self.timer_selected_auto < self.timer.auto_run.len() {
                      self.timer.auto_run.remove(self.timer_selected_auto);
@@ -264,6 +541,242 @@ impl App {
         }
     }
 
+    // Keys while an overlay is open. Any key closes the help; the palette and task picker are typed into.
+    pub fn handle_overlay_key(&mut self, key: KeyEvent) {
+        if let Some(Overlay::Tasks(_)) = &self.overlay {
+            self.handle_task_key(key);
+            return;
+        }
+        let Some(Overlay::Palette(palette)) = &mut self.overlay else {
+            self.overlay = None;
+            return;
+        };
+        let commands = crate::palette::commands(&self.themes);
+        let matches = palette.matches(&commands);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.overlay = None,
+            KeyCode::Enter => {
+                let chosen = matches.get(palette.selected).map(|&i| commands[i].actions.clone());
+                self.overlay = None;
+                for action in chosen.unwrap_or_default() {
+                    self.handle_input(action);
+                }
+            }
+            KeyCode::Up => palette.selected = palette.selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => palette.selected = palette.selected.saturating_sub(1),
+            KeyCode::Down if palette.selected + 1 < matches.len() => palette.selected += 1,
+            KeyCode::Char('n') if ctrl && palette.selected + 1 < matches.len() => palette.selected += 1,
+            KeyCode::Backspace => {
+                palette.query.pop();
+                palette.selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                palette.query.push(c);
+                palette.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
+    // Enter makes the selected task the active one, adding it first when it's new. Ctrl+D finishes it.
+    fn handle_task_key(&mut self, key: KeyEvent) {
+        let Some(Overlay::Tasks(picker)) = &mut self.overlay else { return };
+        let choices = picker.choices(&self.tasks);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.overlay = None,
+            KeyCode::Enter => {
+                self.tasks.active = match choices.get(picker.selected) {
+                    Some(crate::tasks::TaskChoice::Task(id)) => Some(*id),
+                    Some(crate::tasks::TaskChoice::Add(name, project, estimate)) => Some(self.tasks.add(name, project, *estimate)),
+                    Some(crate::tasks::TaskChoice::NoTask) => None,
+                    None => return,
+                };
+                self.overlay = None;
+            }
+            KeyCode::Char('d') if ctrl => {
+                if let Some(crate::tasks::TaskChoice::Task(id)) = choices.get(picker.selected) {
+                    self.tasks.set_done(*id, true);
+                    picker.selected = picker.selected.min(choices.len().saturating_sub(2));
+                }
+            }
+            KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down if picker.selected + 1 < choices.len() => picker.selected += 1,
+            KeyCode::Char('n') if ctrl && picker.selected + 1 < choices.len() => picker.selected += 1,
+            KeyCode::Backspace => {
+                picker.query.pop();
+                picker.selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                picker.query.push(c);
+                picker.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
+    // Clicks and the wheel, matched against where the last frame drew things
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.overlay.is_some() {
+            self.overlay = None;
+            return;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let now = Instant::now();
+                let double = self.last_click.is_some_and(|(at, column, row)| {
+                    now.duration_since(at) < Duration::from_millis(400) && (column, row) == (mouse.column, mouse.row)
+                });
+                // A third click starts a new pair instead of counting as another double-click
+                self.last_click = if double { None } else { Some((now, mouse.column, mouse.row)) };
+                self.click(position, double);
+            }
+            MouseEventKind::ScrollUp => self.scroll(position, 1),
+            MouseEventKind::ScrollDown => self.scroll(position, -1),
+            _ => {}
+        }
+    }
+
+    fn click(&mut self, position: Position, double: bool) {
+        let areas = self.hit_areas.clone();
+        if let Some((_, n)) = areas.tabs.iter().find(|(rect, _)| rect.contains(position)) {
+            self.handle_input(InputAction::Tab(*n));
+            return;
+        }
+        match self.tab {
+            Tab::Timer if areas.sessions.contains(position) => {
+                let index = (position.y - areas.sessions.y) as usize;
+                let sessions = [SessionType::Focus, SessionType::ShortBreak, SessionType::LongBreak];
+                if index < sessions.len() {
+                    self.focus = Focus::Left;
+                    self.timer_selected_session = index;
+                    if double && !self.is_running(sessions[index]) {
+                        self.handle_input(InputAction::Space);
+                    }
+                }
+            }
+            Tab::Timer if areas.auto_run.contains(position) => {
+                let index = self.timer_auto_list_state.offset() + (position.y - areas.auto_run.y) as usize;
+                if index < self.timer.auto_run.len() {
+                    self.focus = Focus::Right;
+                    self.timer_selected_auto = index;
+                    self.timer_auto_list_state.select(Some(index));
+                    if double && !self.is_running(self.timer.auto_run[index]) {
+                        self.handle_input(InputAction::Space);
+                    }
+                }
+            }
+            Tab::Timer if areas.timer.contains(position) => self.toggle_pause(),
+            Tab::Settings if areas.settings_list.contains(position) => {
+                let index = (position.y - areas.settings_list.y) as usize + self.settings_list_state.offset();
+                if index <= LAST_SETTING {
+                    self.focus = Focus::Left;
+                    self.settings_selected = index;
+                }
+            }
+            Tab::Settings if areas.settings_values.contains(position) => self.focus = Focus::Right,
+            Tab::Stats if areas.heatmap.contains(position) => {
+                let (column, row) = (position.x - areas.heatmap.x, position.y - areas.heatmap.y);
+                if let Some(day) = crate::ui::heatmap_ui::day_at(Local::now().date_naive(), column, row) {
+                    self.focus = Focus::Right;
+                    self.heatmap_cursor = day;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn scroll(&mut self, position: Position, delta: i64) {
+        match self.tab {
+            Tab::Timer if self.hit_areas.auto_run.contains(position) => {
+                self.focus = Focus::Right;
+                match delta {
+                    1 => self.handle_up(),
+                    _ => self.handle_down(),
+                }
+            }
+            // Over the list the wheel picks a setting, over the values it changes one
+            Tab::Settings if self.hit_areas.settings_list.contains(position) => {
+                self.focus = Focus::Left;
+                match delta {
+                    1 => self.handle_up(),
+                    _ => self.handle_down(),
+                }
+            }
+            Tab::Settings if self.hit_areas.settings_values.contains(position) => {
+                self.focus = Focus::Right;
+                self.adjust_setting(delta);
+            }
+            _ => {}
+        }
+    }
+
+    // The arrows move the heatmap cursor once Enter is pressed on the heatmap
+    pub fn in_heatmap(&self) -> bool {
+        self.tab == Tab::Stats && self.stats_selected == 9 && self.focus == Focus::Right
+    }
+
+    // Streaks follow the goal settings. Changing those isn't breaking a streak, so no event.
+    fn refresh_streaks(&mut self) {
+        self.garden.update_streaks(&self.settings.goals.streak_days(&self.history));
+        self.last_streak = self.garden.current_streak;
+    }
+
+    // Only focus sessions are spent on a task
+    fn session_task(&self) -> Option<u32> {
+        match self.timer.session_type {
+            SessionType::Focus => self.tasks.active_task().map(|t| t.id),
+            _ => None,
+        }
+    }
+
+    // A double-click starts a session, so it leaves one that is already running alone
+    fn is_running(&self, session_type: SessionType) -> bool {
+        self.timer.session_type == session_type && self.timer.state == TimerState::Running
+    }
+
+    // Starts the current session over; one that hasn't started just goes back to full length
+    fn reset_session(&mut self) {
+        let in_progress = matches!(self.timer.state, TimerState::Running | TimerState::Paused | TimerState::Suspended);
+        if !in_progress {
+            self.timer.stop();
+            return;
+        }
+        let auto_run_index = self.timer.auto_run_index;
+        self.start_session(self.timer.session_type);
+        self.timer.auto_run_index = auto_run_index;
+    }
+
+    fn export_stats(&mut self, format: crate::cli::ExportFormat) {
+        let extension = match format {
+            crate::cli::ExportFormat::Json => "json",
+            crate::cli::ExportFormat::Csv => "csv",
+        };
+        let path = crate::storage::data_dir().join(format!("taman-export-{}.{}", Local::now().format("%Y%m%d-%H%M%S"), extension));
+        self.status_message = Some(match crate::cli::export(self, format, Some(path.clone())) {
+            Ok(_) => format!("Exported stats to {}", path.display()),
+            Err(err) => format!("Couldn't export stats: {}", err),
+        });
+    }
+
+    fn toggle_pause(&mut self) {
+        let request = match self.timer.state {
+            TimerState::Running => Request::Pause,
+            TimerState::Paused => Request::Resume,
+            _ => return,
+        };
+        if self.daemon.is_some() {
+            self.send_to_daemon(request);
+        } else if self.timer.state == TimerState::Running {
+            self.timer.pause();
+        } else {
+            self.timer.resume();
+        }
+    }
+
     fn handle_up(&mut self) {
         match self.tab {
             Tab::Timer => {
@@ -285,10 +798,11 @@ impl App {
                     self.adjust_setting(1);
                 }
             }
-            Tab::Stats => {
-                if self.stats_selected > 0 {
-                    self.stats_selected -= 1;
-                }
+            Tab::Stats if self.stats_selected > 0 => {
+                self.stats_selected -= 1;
+            }
+            Tab::Garden if self.garden_selected >= self.garden_columns => {
+                self.garden_selected -= self.garden_columns;
             }
             _ => {}
         }
@@ -309,8 +823,7 @@ impl App {
             }
             Tab::Settings => {
                 if self.focus == Focus::Left {
-                    let max = 3; // Focus, Short, Long, Theme
-                    if self.settings_selected < max {
+                    if self.settings_selected < LAST_SETTING {
                         self.settings_selected += 1;
                     }
                 } else {
@@ -318,11 +831,14 @@ impl App {
                 }
             }
             Tab::Stats => {
-                let max = 8; // 9 categories
+                let max = 12; // 9 categories, the heatmap, focus patterns, goals and tasks
                 if self.stats_selected < max {
                     self.stats_selected += 1;
                 }
             }
+            Tab::Garden if self.garden_selected + self.garden_columns < self.garden_plants().len() => {
+                self.garden_selected += self.garden_columns;
+            }
             _ => {}
         }
     }
@@ -330,51 +846,521 @@ impl App {
     fn adjust_setting(&mut self, delta: i64) {
         match self.settings_selected {
             0 => { // Focus
-                self.settings.focus_duration = (self.settings.focus_duration as i64 + delta).clamp(1, 60) as u64;
-                if self.timer.session_type == SessionType::Focus {
+                self.settings.focus_duration = self.stepped("focus_duration", self.settings.focus_duration, delta);
+                // A session in progress keeps its length; the new one applies from the next
+                if self.timer.session_type == SessionType::Focus && self.timer.state == TimerState::Idle {
                     self.timer.set_session(SessionType::Focus, &self.settings);
                 }
             }
             1 => { // Short break
-                self.settings.short_break_duration = (self.settings.short_break_duration as i64 + delta).clamp(1, 60) as u64;
-                if self.timer.session_type == SessionType::ShortBreak {
+                self.settings.short_break_duration = self.stepped("short_break_duration", self.settings.short_break_duration, delta);
+                if self.timer.session_type == SessionType::ShortBreak && self.timer.state == TimerState::Idle {
                     self.timer.set_session(SessionType::ShortBreak, &self.settings);
                 }
             }
             2 => { // Long break
-                self.settings.long_break_duration = (self.settings.long_break_duration as i64 + delta).clamp(1, 60) as u64;
-                if self.timer.session_type == SessionType::LongBreak {
+                self.settings.long_break_duration = self.stepped("long_break_duration", self.settings.long_break_duration, delta);
+                if self.timer.session_type == SessionType::LongBreak && self.timer.state == TimerState::Idle {
                     self.timer.set_session(SessionType::LongBreak, &self.settings);
                 }
             }
             3 => { // Theme
-                let themes = [ThemeVariant::System, ThemeVariant::RosePineDawn, ThemeVariant::RosePine, ThemeVariant::GruvboxDark, ThemeVariant::GruvboxLight, ThemeVariant::SolarizedDark, ThemeVariant::SolarizedLight, ThemeVariant::Nord, ThemeVariant::TokyoNight, ThemeVariant::Monokai, ThemeVariant::Vesper, ThemeVariant::Everforest, ThemeVariant::CatppuccinLatte, ThemeVariant::CatppuccinFrappe, ThemeVariant::CatppuccinMacchiato, ThemeVariant::CatppuccinMocha];
-                let current = themes.iter().position(|&t| t == self.settings.theme).unwrap_or(0);
+                let themes = &self.themes.list;
+                let current = self.themes.position(&self.settings.theme).unwrap_or(0);
                 let new_index = (current as i64 - delta).rem_euclid(themes.len() as i64) as usize;
-                self.settings.theme = themes[new_index];
-                self.theme = Theme::new(self.settings.theme);
+                self.settings.theme = themes[new_index].name.clone();
+                self.theme = themes[new_index].theme;
+            }
+            4 => { // Pomodoro cycle
+                self.settings.cycle_mode = !self.settings.cycle_mode;
+            }
+            5 => { // Long break interval
+                self.settings.long_break_interval = self.stepped("long_break_interval", self.settings.long_break_interval as u64, delta) as u32;
+            }
+            6 => { // Auto-start breaks
+                self.settings.auto_start_breaks = !self.settings.auto_start_breaks;
+            }
+            7 => { // Auto-start focus
+                self.settings.auto_start_focus = !self.settings.auto_start_focus;
+            }
+            8 => { // Growth mode
+                self.settings.growth.mode = match self.settings.growth.mode {
+                    GrowthMode::PerSession => GrowthMode::PerMinute,
+                    GrowthMode::PerMinute => GrowthMode::PerSession,
+                };
+            }
+            9 => { // Growth points
+                self.settings.growth.points = self.stepped("growth.points", self.settings.growth.points as u64, delta) as u32;
+            }
+            10 => { // Minimum session length
+                self.settings.growth.min_minutes = self.stepped("growth.min_minutes", self.settings.growth.min_minutes, delta);
+            }
+            11 => { // Focus grows plant
+                self.settings.growth.focus = !self.settings.growth.focus;
+            }
+            12 => { // Short breaks grow plant
+                self.settings.growth.short_breaks = !self.settings.growth.short_breaks;
+            }
+            13 => { // Long breaks grow plant
+                self.settings.growth.long_breaks = !self.settings.growth.long_breaks;
+            }
+            14 => { // Next seed: Random followed by every species
+                let current = match &self.settings.next_species {
+                    Some(id) => crate::species::SPECIES.iter().position(|s| s.id == id).map(|i| i + 1).unwrap_or(0),
+                    None => 0,
+                };
+                let choices = crate::species::SPECIES.len() as i64 + 1;
+                let new_index = (current as i64 - delta).rem_euclid(choices) as usize;
+                self.settings.next_species = match new_index {
+                    0 => None,
+                    i => Some(crate::species::SPECIES[i - 1].id.to_string()),
+                };
+            }
+            15 => { // Strict mode
+                self.settings.strict_mode = !self.settings.strict_mode;
+            }
+            16 => { // Wither penalty
+                self.settings.wither_penalty = self.stepped("wither_penalty", self.settings.wither_penalty as u64, delta) as u32;
+            }
+            17 => { // Strikes before withering
+                self.settings.wither_strikes = self.stepped("wither_strikes", self.settings.wither_strikes as u64, delta) as u32;
+            }
+            18 => { // Goal unit
+                self.settings.goals.switch_unit(self.settings.focus_duration);
+                self.refresh_streaks();
+            }
+            19 => { // Daily goal, off at 0
+                let step = match self.settings.goals.unit {
+                    crate::goals::GoalUnit::Sessions => 1,
+                    crate::goals::GoalUnit::Minutes => 15,
+                };
+                self.settings.goals.daily = self.stepped("goals.daily", self.settings.goals.daily, delta * step);
+                self.refresh_streaks();
+            }
+            20 => { // Weekly goal, off at 0
+                let step = match self.settings.goals.unit {
+                    crate::goals::GoalUnit::Sessions => 1,
+                    crate::goals::GoalUnit::Minutes => 60,
+                };
+                self.settings.goals.weekly = self.stepped("goals.weekly", self.settings.goals.weekly, delta * step);
+            }
+            21 => { // Streaks need the daily goal
+                self.settings.goals.streak_needs_goal = !self.settings.goals.streak_needs_goal;
+                self.refresh_streaks();
             }
             _ => {}
         }
     }
 
-    pub fn save(&self) {
-        let mut statistics = self.statistics.clone();
-        statistics.current_streak = self.garden.current_streak;
-        statistics.longest_streak = self.garden.longest_streak;
-        statistics.current_streak_start_date = self.garden.current_streak_start_date;
-        statistics.longest_streak_end_date = self.garden.longest_streak_end_date;
-        statistics.current_streak_dates = self.garden.current_streak_dates.clone();
-        statistics.longest_streak_dates = self.garden.longest_streak_dates.clone();
-        let data = Data {
+    // A numeric setting moved by `delta`, kept within its limits
+    fn stepped(&self, key: &str, value: u64, delta: i64) -> u64 {
+        let Some(range) = self.settings.range(key) else { return value };
+        (value as i64 + delta).clamp(*range.start() as i64, *range.end() as i64) as u64
+    }
+
+    // Completed plants shown on the Garden tab, honouring the month filter
+    pub fn garden_plants(&self) -> Vec<&crate::garden::CompletedPlant> {
+        self.garden.completed_plants.iter().filter(|p| {
+            let local = p.completed_at.with_timezone(&Local);
+            self.garden_month.is_none_or(|(year, month)| local.year() == year && local.month() == month)
+        }).collect()
+    }
+
+    // Periods since the first session, the current one included; the Stats tab goes no further back
+    fn stats_period_count(&self) -> i64 {
+        self.stats_period.count(self.stats_first_day(), Local::now().date_naive())
+    }
+
+    // The day of the first session or grown plant, today when there are none
+    pub fn stats_first_day(&self) -> NaiveDate {
+        let today = Local::now().date_naive();
+        let first_session = self.history.sessions.first().map(|s| s.end_time.date_naive());
+        let first_plant = self.garden.completed_plants.iter().map(|p| p.completed_at.with_timezone(&Local).date_naive()).min();
+        first_session.into_iter().chain(first_plant).chain([today]).min().unwrap_or(today)
+    }
+
+    // First day of the period the Stats tab shows
+    pub fn stats_period_start(&self) -> NaiveDate {
+        let current = self.stats_period.start(Local::now().date_naive());
+        self.stats_period.shift(current, -(self.stats_offset as i64))
+    }
+
+    pub fn garden_months(&self) -> Vec<(i32, u32)> {
+        let mut months: Vec<(i32, u32)> = self.garden.completed_plants.iter().map(|p| {
+            let local = p.completed_at.with_timezone(&Local);
+            (local.year(), local.month())
+        }).collect();
+        months.sort();
+        months.dedup();
+        months
+    }
+
+    fn next_species(&self) -> &'static crate::species::Species {
+        match &self.settings.next_species {
+            Some(id) => crate::species::find(id),
+            None => crate::species::random(),
+        }
+    }
+
+    // Replaces whatever is on the timer, abandoning a session in progress
+    pub fn start_session(&mut self, session_type: SessionType) {
+        if self.daemon.is_some() {
+            self.send_to_daemon(Request::Start { session: session_type, auto_run_index: None });
+            return;
+        }
+        self.abandon_session();
+        self.timer.switch_session(session_type, &self.settings);
+        self.timer.start();
+    }
+
+    // Settings changed from outside the Settings tab; a session in progress keeps its length
+    pub fn apply_settings(&mut self, settings: Settings) {
+        if self.daemon.is_some() {
+            self.send_to_daemon(Request::Settings { settings: Box::new(settings) });
+            return;
+        }
+        let goals_changed = self.settings.goals != settings.goals;
+        self.settings = settings;
+        if goals_changed {
+            self.refresh_streaks();
+        }
+        // `config set theme nord` is saved as "Nord"
+        if let Some(named) = self.themes.find(&self.settings.theme) {
+            self.settings.theme = named.name.clone();
+        }
+        self.theme = self.themes.theme(&self.settings.theme);
+        if self.timer.state == crate::timer::TimerState::Idle {
+            self.timer.set_session(self.timer.session_type, &self.settings);
+        }
+    }
+
+    pub fn data(&self) -> Data {
+        Data {
+            sessions: self.history.sessions.clone(),
+            completed_plants: self.garden.completed_plants.clone(),
+            ..self.state()
+        }
+    }
+
+    // Everything that is saved apart from the session log and the garden
+    fn state(&self) -> Data {
+        Data {
+            schema_version: crate::storage::SCHEMA_VERSION,
+            current_species: self.plant.species.clone(),
             current_plant_stage: self.plant.stage.to_u32(),
             growth_points: self.plant.growth_points,
+            current_planted_at: self.plant.planted_at,
+            current_plant_sessions: self.plant.sessions,
+            current_plant_strikes: self.plant.strikes,
             settings: self.settings.clone(),
-            statistics,
-            completed_plants: self.garden.completed_plants.clone(),
+            sessions: vec![],
+            completed_plants: vec![],
             auto_run: self.timer.auto_run.clone(),
             auto_run_index: self.timer.auto_run_index,
+            active_session: self.timer.snapshot(),
+            cycle_completed: self.timer.cycle_completed,
+            last_streak: self.last_streak,
+            tasks: self.tasks.clone(),
+        }
+    }
+
+    // Giving up on a focus session withers the plant in strict mode; switching or resetting doesn't
+    pub fn stop_session(&mut self) {
+        let in_progress = matches!(self.timer.state, TimerState::Running | TimerState::Paused | TimerState::Suspended);
+        let gave_up = in_progress && self.timer.session_type == SessionType::Focus;
+        self.abandon_session();
+        self.timer.stop();
+        if gave_up && self.settings.strict_mode && self.plant.wither(self.settings.wither_penalty, self.settings.wither_strikes) {
+            self.garden.add_withered_plant(self.plant.clone(), Utc::now());
+            self.plant = Plant::new(self.next_species());
+        }
+    }
+
+    // Timer actions go to the daemon as they are; the App only mirrors what it reports back
+    fn daemon_request(&self, action: InputAction) -> Option<Request> {
+        let state = self.timer.state;
+        match action {
+            InputAction::Space if self.tab == Tab::Timer => {
+                let (session, auto_run_index) = match self.focus {
+                    Focus::Left => ([SessionType::Focus, SessionType::ShortBreak, SessionType::LongBreak][self.timer_selected_session], None),
+                    Focus::Right => (*self.timer.auto_run.get(self.timer_selected_auto)?, Some(self.timer_selected_auto)),
+                };
+                match state {
+                    _ if self.timer.session_type != session => Some(Request::Start { session, auto_run_index }),
+                    TimerState::Idle => Some(Request::Start { session, auto_run_index }),
+                    TimerState::Running => Some(Request::Pause),
+                    TimerState::Paused => Some(Request::Resume),
+                    _ => None,
+                }
+            }
+            InputAction::Stop if self.tab == Tab::Timer => Some(Request::Stop),
+            InputAction::Reset if self.tab == Tab::Timer && matches!(state, TimerState::Running | TimerState::Paused) => {
+                Some(Request::Start { session: self.timer.session_type, auto_run_index: self.timer.auto_run_index })
+            }
+            InputAction::CountGap if state == TimerState::Suspended => Some(Request::CountGap),
+            InputAction::DiscardGap if state == TimerState::Suspended => Some(Request::DiscardGap),
+            InputAction::EndSession if state == TimerState::Suspended => Some(Request::EndSession),
+            _ => None,
+        }
+    }
+
+    fn send_to_daemon(&mut self, request: Request) {
+        let Some(daemon) = &mut self.daemon else { return };
+        match daemon.request(&request) {
+            Ok(status) => self.mirror(status),
+            Err(err) => self.status_message = Some(format!("Daemon: {}", err)),
+        }
+    }
+
+    // Makes the local copy match the daemon, which owns the timer and does all the saving
+    fn mirror(&mut self, status: DaemonStatus) {
+        let Some(daemon) = &mut self.daemon else { return };
+        let progress_changed = daemon.last.progress != status.progress;
+        daemon.last = status.clone();
+        match &status.active {
+            Some(active) => self.timer.restore(active),
+            None => {
+                self.timer.set_session(status.session_type, &status.settings);
+                self.timer.state = status.state;
+            }
+        }
+        self.timer.remaining_seconds = status.remaining_seconds;
+        self.timer.auto_run = status.auto_run;
+        self.timer.auto_run_index = status.auto_run_index;
+        self.timer.cycle_completed = status.cycle_completed;
+        self.tasks = status.tasks;
+        if self.settings != status.settings {
+            let goals_changed = self.settings.goals != status.settings.goals;
+            self.settings = status.settings;
+            self.theme = self.themes.theme(&self.settings.theme);
+            if goals_changed {
+                self.garden.update_streaks(&self.settings.goals.streak_days(&self.history));
+            }
+        }
+        if progress_changed {
+            let (data, _) = self.storage.load();
+            self.reload_progress(data);
+        }
+    }
+
+    fn detach_daemon(&mut self) {
+        self.daemon = None;
+        self.data_lock = crate::storage::lock_data().ok().flatten();
+        let (data, _) = self.storage.load();
+        match &data.active_session {
+            Some(active) => self.timer.restore(active),
+            None => self.timer.set_session(self.timer.session_type, &self.settings),
+        }
+        self.reload_progress(data);
+        self.seen_start = self.timer.started_at;
+        self.last_streak = self.garden.current_streak;
+        self.status_message = Some("The daemon stopped, the timer runs in this window again".to_string());
+    }
+
+    fn reload_progress(&mut self, data: Data) {
+        self.plant = saved_plant(&data);
+        self.tasks = data.tasks;
+        self.garden.completed_plants = data.completed_plants;
+        self.history = History::new(data.sessions);
+        self.garden.update_streaks(&self.settings.goals.streak_days(&self.history));
+    }
+
+    pub fn save(&mut self) {
+        if self.read_only {
+            return;
+        }
+        // Only queue, settings and task edits are left to hand over; the daemon saves everything
+        if let Some(last) = self.daemon.as_ref().map(|d| d.last.clone()) {
+            // Taken first, the reply to one request would otherwise overwrite the other edits
+            let (settings, auto_run, auto_run_index, tasks) = (self.settings.clone(), self.timer.auto_run.clone(), self.timer.auto_run_index, self.tasks.clone());
+            if settings != last.settings {
+                self.send_to_daemon(Request::Settings { settings: Box::new(settings) });
+            }
+            if auto_run != last.auto_run || auto_run_index != last.auto_run_index {
+                self.send_to_daemon(Request::Queue { auto_run, auto_run_index });
+            }
+            if tasks != last.tasks {
+                self.send_to_daemon(Request::Tasks { tasks });
+            }
+            return;
+        }
+        let data = self.data();
+        match self.storage.save(&data) {
+            Ok(()) => self.saved = Some(self.fingerprint()),
+            Err(err) => self.status_message = Some(format!("Couldn't save progress: {}", err)),
+        }
+    }
+
+    // Most keys and clicks only move a selection, which isn't worth rewriting the file for
+    pub fn save_if_changed(&mut self) {
+        if self.daemon.is_some() || self.saved.as_ref() != Some(&self.fingerprint()) {
+            self.save();
+        }
+    }
+
+    // The log and the garden only ever grow, so their lengths tell whether they changed
+    fn fingerprint(&self) -> (serde_json::Value, usize, usize) {
+        let state = serde_json::to_value(self.state()).unwrap_or_default();
+        (state, self.history.sessions.len(), self.garden.completed_plants.len())
+    }
+}
+
+fn saved_plant(data: &Data) -> Plant {
+    let mut plant = Plant::from_stage(&data.current_species, data.current_plant_stage, data.growth_points);
+    plant.planted_at = data.current_planted_at;
+    plant.sessions = data.current_plant_sessions;
+    plant.strikes = data.current_plant_strikes;
+    plant
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::ActiveSession;
+    use chrono::TimeDelta;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    // Hands out a prepared document and keeps whatever is saved
+    #[derive(Debug)]
+    struct MemoryStorage {
+        data: Data,
+        saves: Rc<Cell<usize>>,
+    }
+
+    impl Storage for MemoryStorage {
+        fn load(&mut self) -> (Data, Option<String>) {
+            (self.data.clone(), None)
+        }
+
+        fn save(&mut self, data: &Data) -> std::io::Result<()> {
+            self.data = data.clone();
+            self.saves.set(self.saves.get() + 1);
+            Ok(())
+        }
+
+        fn sessions_between(&self, _from: NaiveDate, _to: NaiveDate) -> std::io::Result<Vec<crate::storage::SessionLog>> {
+            Ok(self.data.sessions.clone())
+        }
+    }
+
+    fn app_from(data: Data) -> App {
+        app_counting_saves(data).0
+    }
+
+    fn app_counting_saves(data: Data) -> (App, Rc<Cell<usize>>) {
+        let saves = Rc::new(Cell::new(0));
+        let storage = MemoryStorage { data, saves: saves.clone() };
+        (App::with_storage(Box::new(storage), None, None, false), saves)
+    }
+
+    fn app_with(settings: Settings, active_session: ActiveSession) -> App {
+        app_from(Data { settings, active_session: Some(active_session), ..Data::default() })
+    }
+
+    #[test]
+    fn credits_a_session_that_ended_while_closed() {
+        let started_at = Local::now() - TimeDelta::minutes(27);
+        let cycle = Settings { cycle_mode: true, ..Settings::default() };
+        let app = app_with(cycle, ActiveSession {
+            session_type: SessionType::Focus,
+            state: TimerState::Running,
+            duration_seconds: 25 * 60,
+            started_at,
+            paused_at: None,
+            paused_seconds: 0,
+        });
+        let focus: Vec<_> = app.history.completed(crate::history::SessionKind::Focus).collect();
+        assert_eq!(focus.len(), 1);
+        assert_eq!(focus[0].end_time, started_at + TimeDelta::minutes(25));
+        // In a cycle the break starts on its own, from the moment the focus session ended
+        assert_eq!(app.timer.session_type, SessionType::ShortBreak);
+        assert_eq!(app.timer.state, TimerState::Running);
+        assert_eq!(app.timer.started_at, Some(started_at + TimeDelta::minutes(25)));
+        assert!((175..=180).contains(&app.timer.remaining_seconds));
+    }
+
+    #[test]
+    fn restores_a_paused_session_as_it_was() {
+        let started_at = Local::now() - TimeDelta::hours(2);
+        let app = app_with(Settings::default(), ActiveSession {
+            session_type: SessionType::Focus,
+            state: TimerState::Paused,
+            duration_seconds: 25 * 60,
+            started_at,
+            paused_at: Some(started_at + TimeDelta::minutes(10)),
+            paused_seconds: 0,
+        });
+        assert_eq!(app.timer.state, TimerState::Paused);
+        assert_eq!(app.timer.remaining_seconds, 15 * 60);
+        assert!(app.history.sessions.is_empty());
+    }
+
+    #[test]
+    fn the_garden_filters_plants_by_month() {
+        let plant = |completed_at: &str| crate::garden::CompletedPlant {
+            plant: Plant::new(crate::species::find("cactus")),
+            completed_at: completed_at.parse().unwrap(),
+            withered: false,
         };
-        save_data(&data);
+        let mut app = app_from(Data {
+            completed_plants: vec![plant("2026-01-10T12:00:00Z"), plant("2026-03-15T12:00:00Z"), plant("2026-01-20T12:00:00Z")],
+            ..Data::default()
+        });
+        app.tab = Tab::Garden;
+        assert_eq!(app.garden_months(), vec![(2026, 1), (2026, 3)]);
+        assert_eq!(app.garden_plants().len(), 3);
+        // Going back from "all" steps through the months, newest first
+        app.handle_input(InputAction::PrevPeriod);
+        assert_eq!(app.garden_month, Some((2026, 3)));
+        assert_eq!(app.garden_plants().len(), 1);
+        app.handle_input(InputAction::PrevPeriod);
+        assert_eq!(app.garden_plants().len(), 2);
+        app.handle_input(InputAction::PrevPeriod);
+        assert_eq!(app.garden_month, Some((2026, 1)));
+        app.handle_input(InputAction::NextPeriod);
+        app.handle_input(InputAction::NextPeriod);
+        assert_eq!(app.garden_month, None);
+    }
+
+    #[test]
+    fn only_giving_up_on_focus_withers_in_strict_mode() {
+        let strict = Settings { strict_mode: true, ..Settings::default() };
+        let mut app = app_with(strict, ActiveSession {
+            session_type: SessionType::Focus,
+            state: TimerState::Running,
+            duration_seconds: 25 * 60,
+            started_at: Local::now() - TimeDelta::minutes(5),
+            paused_at: None,
+            paused_seconds: 0,
+        });
+        app.tab = Tab::Timer;
+        app.focus = Focus::Left;
+        app.handle_input(InputAction::Reset);
+        // Switching to a break, and from the break back to focus
+        app.timer_selected_session = 1;
+        app.handle_input(InputAction::Space);
+        app.start_session(SessionType::Focus);
+        assert_eq!(app.plant.strikes, 0);
+        assert_eq!(app.history.sessions.iter().filter(|s| s.outcome == crate::storage::SessionOutcome::Abandoned).count(), 3);
+
+        app.handle_input(InputAction::Stop);
+        assert_eq!(app.plant.strikes, 1);
+        assert_eq!(app.timer.state, TimerState::Idle);
+    }
+
+    #[test]
+    fn moving_around_does_not_rewrite_the_file() {
+        let (mut app, saves) = app_counting_saves(Data::default());
+        app.save_if_changed();
+        assert_eq!(saves.get(), 1);
+        app.handle_input(InputAction::Tab(4));
+        app.handle_input(InputAction::Down);
+        app.save_if_changed();
+        assert_eq!(saves.get(), 1);
+        app.handle_input(InputAction::Right);
+        app.handle_input(InputAction::Up); // short break one minute longer
+        app.save_if_changed();
+        assert_eq!(saves.get(), 2);
     }
-}
\ No newline at end of file
+}
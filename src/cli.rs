@@ -0,0 +1,519 @@
+use std::error::Error;
+use std::fs;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::{json, Value};
+
+use crate::app::App;
+use crate::history::{History, SessionKind};
+use crate::plant::Plant;
+use crate::storage::{SessionOutcome, Settings};
+use crate::timer::{SessionType, Timer, TimerState};
+
+/// A TUI Pomodoro productivity app where your focus sessions grow plants.
+/// Run without a command to open the interface.
+#[derive(Parser)]
+#[command(name = "taman", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show the current session, plant and today's progress
+    Status {
+        /// One line built from {remaining} {minutes} {type} {state} {icon} {plant} {species} {progress} {focus_today} {focus_minutes_today} {task}
+        #[arg(short, long)]
+        format: Option<String>,
+        /// Print a waybar custom module object (text, tooltip, class, alt, percentage)
+        #[arg(long)]
+        json: bool,
+        /// Print again every second
+        #[arg(short, long)]
+        watch: bool,
+    },
+    /// Show totals and streaks
+    Stats {
+        /// Only count sessions from this day on (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+    },
+    /// Write all data to stdout or a file
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// File to write instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Start a session; it keeps running while taman is closed
+    Start {
+        #[arg(value_enum)]
+        session: SessionArg,
+        /// Abandon a session that is already in progress
+        #[arg(long)]
+        force: bool,
+    },
+    /// Run the timer in the background and accept commands on a local socket
+    Daemon,
+    /// Read or change settings
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Add, list, pick and finish the tasks focus sessions are spent on
+    Task {
+        #[command(subcommand)]
+        action: TaskAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print one setting, or every setting when no key is given
+    Get { key: Option<String> },
+    /// Change a setting, e.g. `focus_duration 50` or `growth.mode PerMinute`
+    Set { key: String, value: String },
+}
+
+#[derive(Subcommand)]
+pub enum TaskAction {
+    /// Add a task
+    Add {
+        name: String,
+        #[arg(short, long, default_value = "")]
+        project: String,
+        /// Pomodoros you expect it to take
+        #[arg(short, long, default_value_t = 0)]
+        estimate: u32,
+        /// Make it the current task right away
+        #[arg(long)]
+        pick: bool,
+    },
+    /// List open tasks with the pomodoros spent on them
+    List {
+        /// Include finished tasks
+        #[arg(short, long)]
+        all: bool,
+    },
+    /// Record the next focus sessions against a task, given by number or name; `none` clears it
+    Pick { task: String },
+    /// Mark a task as finished
+    Done { task: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Everything taman stores, in the data.json layout
+    Json,
+    /// The session log only
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SessionArg {
+    Focus,
+    #[value(alias = "short-break")]
+    Short,
+    #[value(alias = "long-break")]
+    Long,
+}
+
+impl Command {
+    // Commands that change the saved data rather than only show it
+    fn writes(&self) -> bool {
+        match self {
+            Command::Start { .. } => true,
+            Command::Config { action } => matches!(action, ConfigAction::Set { .. }),
+            Command::Task { action } => !matches!(action, TaskAction::List { .. }),
+            Command::Status { .. } | Command::Daemon | Command::Stats { .. } | Command::Export { .. } => false,
+        }
+    }
+}
+
+impl SessionArg {
+    fn session_type(self) -> SessionType {
+        match self {
+            SessionArg::Focus => SessionType::Focus,
+            SessionArg::Short => SessionType::ShortBreak,
+            SessionArg::Long => SessionType::LongBreak,
+        }
+    }
+}
+
+pub fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Status { format, json, watch } => return status(format, json, watch),
+        Command::Daemon => return crate::daemon::run(),
+        _ => {}
+    }
+    // Loading through App credits sessions that finished while nothing was running,
+    // in memory only until the command turns out to write
+    let mut app = App::read_only();
+    // Bells and OSC sequences belong to the TUI; here they would end up in the command's output
+    app.notifiers.terminal = false;
+    if let Some(notice) = app.status_message.take() {
+        eprintln!("taman: {}", notice);
+    }
+    let writes = command.writes();
+    // A TUI without the daemon would save over anything written here
+    let _lock = match writes && app.daemon.is_none() {
+        true => Some(crate::storage::lock_data()?.ok_or(
+            "taman is open in another terminal; make the change there, or run `taman daemon` so both can share the data",
+        )?),
+        false => None,
+    };
+    if writes {
+        // What loading credited runs its hooks below, so it must not be credited again next time
+        app.read_only = false;
+        save(&mut app)?;
+    } else {
+        // Nothing is saved, so sessions credited while loading are left to the next run that saves
+        app.events.clear();
+    }
+    let output = match command {
+        Command::Status { .. } | Command::Daemon => unreachable!("handled without loading the app"),
+        Command::Stats { since } => stats(&app, since)?,
+        Command::Export { format, output } => export(&app, format, output)?,
+        Command::Start { session, force } => {
+            let in_progress = matches!(app.timer.state, TimerState::Running | TimerState::Paused | TimerState::Suspended);
+            if in_progress && !force {
+                return Err(format!("a {} session is already in progress, pass --force to replace it", app.timer.session_type.name()).into());
+            }
+            app.start_session(session.session_type());
+            save(&mut app)?;
+            format!("Started {} ({} min)\n", app.timer.session_type.name(), app.timer.duration_seconds / 60)
+        }
+        Command::Config { action } => config(&mut app, action)?,
+        Command::Task { action } => task(&mut app, action)?,
+    };
+    print(&output)?;
+    app.finish_events();
+    if let Some(err) = app.status_message.take() {
+        eprintln!("taman: {}", err);
+    }
+    Ok(())
+}
+
+// Returns false once stdout is closed. A closed pipe (`taman export | head`) is not worth an error.
+fn print(output: &str) -> Result<bool, Box<dyn Error>> {
+    let mut stdout = io::stdout();
+    match stdout.write_all(output.as_bytes()).and_then(|_| stdout.flush()) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn save(app: &mut App) -> Result<(), Box<dyn Error>> {
+    app.save();
+    match app.status_message.take() {
+        Some(err) => Err(err.into()),
+        None => Ok(()),
+    }
+}
+
+fn status(format: Option<String>, json: bool, watch: bool) -> Result<(), Box<dyn Error>> {
+    loop {
+        let status = StatusLine::load();
+        let output = match (&format, json) {
+            (_, true) => status.waybar(format.as_deref().unwrap_or(DEFAULT_STATUS_FORMAT)) + "\n",
+            (Some(format), false) => status.render(format) + "\n",
+            (None, false) => status.summary(),
+        };
+        if !print(&output)? || !watch {
+            return Ok(());
+        }
+        std::thread::sleep(status.until_next_second());
+    }
+}
+
+const DEFAULT_STATUS_FORMAT: &str = "{icon} {remaining}";
+
+// Built straight from the saved data and never written back, so status bars
+// polling every second can't race the TUI for the data file
+struct StatusLine {
+    timer: Timer,
+    plant: Plant,
+    focus_today: u32,
+    focus_minutes_today: u64,
+    task: String, // the current task, empty when there is none
+}
+
+impl StatusLine {
+    fn load() -> Self {
+        let (mut storage, _) = crate::storage::open();
+        let (data, _) = storage.load();
+        let mut timer = Timer::new(&data.settings);
+        if let Some(active) = &data.active_session {
+            timer.restore(active);
+        }
+        let plant = Plant::from_stage(&data.current_species, data.current_plant_stage, data.growth_points);
+        let today = Local::now().date_naive();
+        let todays = History::new(storage.sessions_between(today, today).unwrap_or_default());
+        StatusLine {
+            timer,
+            plant,
+            focus_today: todays.total_sessions(SessionKind::Focus),
+            focus_minutes_today: todays.total_minutes(SessionKind::Focus),
+            task: data.tasks.active_task().map(|t| t.name.clone()).unwrap_or_default(),
+        }
+    }
+
+    fn state(&self) -> &'static str {
+        match self.timer.state {
+            // Finished while no taman was running; the next start will credit it
+            TimerState::Running if self.timer.remaining_seconds == 0 => "finished",
+            TimerState::Idle => "idle",
+            TimerState::Running => "running",
+            TimerState::Paused => "paused",
+            TimerState::Suspended => "suspended",
+            TimerState::Finished => "finished",
+        }
+    }
+
+    fn render(&self, format: &str) -> String {
+        let remaining = self.timer.remaining_seconds;
+        format
+            .replace("{remaining}", &format!("{:02}:{:02}", remaining / 60, remaining % 60))
+            .replace("{minutes}", &remaining.div_ceil(60).to_string())
+            .replace("{type}", self.timer.session_type.name())
+            .replace("{state}", self.state())
+            .replace("{icon}", self.plant.stage.icon(self.plant.species()))
+            .replace("{plant}", self.plant.stage_art().name)
+            .replace("{species}", self.plant.species().name)
+            .replace("{progress}", &self.percentage().to_string())
+            .replace("{focus_today}", &self.focus_today.to_string())
+            .replace("{focus_minutes_today}", &self.focus_minutes_today.to_string())
+            .replace("{task}", &self.task)
+    }
+
+    // Wakes up just after the countdown changes, so it never shows a number twice or skips one
+    fn until_next_second(&self) -> Duration {
+        let now = Local::now();
+        let elapsed = match self.timer.started_at {
+            Some(started_at) => (now - started_at - self.timer.paused_total).num_milliseconds(),
+            None => now.timestamp_subsec_millis() as i64,
+        };
+        Duration::from_millis((1000 - elapsed.rem_euclid(1000)) as u64 + 5)
+    }
+
+    fn percentage(&self) -> u64 {
+        (self.timer.progress() * 100.0).round().clamp(0.0, 100.0) as u64
+    }
+
+    fn summary(&self) -> String {
+        self.render("{type} · {state} · {remaining} left\nPlant: {icon} {plant} ({species})\nToday: {focus_today} focus sessions, {focus_minutes_today} minutes focused\n")
+    }
+
+    // https://github.com/Alexays/Waybar/wiki/Module:-Custom
+    fn waybar(&self, format: &str) -> String {
+        let alt = match self.timer.session_type {
+            SessionType::Focus => "focus",
+            SessionType::ShortBreak => "short_break",
+            SessionType::LongBreak => "long_break",
+            SessionType::Unknown => "unknown",
+        };
+        json!({
+            "text": self.render(format),
+            "tooltip": self.summary().trim_end(),
+            "alt": alt,
+            "class": [self.state(), alt],
+            "percentage": self.percentage(),
+        }).to_string()
+    }
+}
+
+fn stats(app: &App, since: Option<NaiveDate>) -> Result<String, Box<dyn Error>> {
+    // From the loaded history, so what was credited while loading counts without a save
+    let history = History::new(
+        app.history.sessions.iter()
+            .filter(|s| since.is_none_or(|since| s.end_time.with_timezone(&Local).date_naive() >= since))
+            .cloned()
+            .collect(),
+    );
+    let plants = app.garden.completed_plants.iter()
+        .filter(|p| !p.withered)
+        .filter(|p| since.is_none_or(|since| p.completed_at.with_timezone(&Local).date_naive() >= since))
+        .count();
+    let abandoned = history.sessions.iter().filter(|s| s.outcome == SessionOutcome::Abandoned).count();
+    let mut out = match since {
+        Some(since) => format!("Since {}\n", since.format("%Y-%m-%d")),
+        None => "All time\n".to_string(),
+    };
+    let _ = writeln!(
+        out,
+        "Sessions:       {} ({} focus, {} breaks)",
+        history.total_sessions(SessionKind::All),
+        history.total_sessions(SessionKind::Focus),
+        history.total_sessions(SessionKind::Break)
+    );
+    let _ = writeln!(
+        out,
+        "Minutes:        {} ({} focused, {} resting)",
+        history.total_minutes(SessionKind::All),
+        history.total_minutes(SessionKind::Focus),
+        history.total_minutes(SessionKind::Break)
+    );
+    let _ = writeln!(out, "Abandoned:      {}", abandoned);
+    let _ = writeln!(out, "Grown plants:   {}", plants);
+    let _ = writeln!(out, "Current streak: {} days", app.garden.current_streak);
+    let _ = writeln!(out, "Longest streak: {} days", app.garden.longest_streak);
+    Ok(out)
+}
+
+pub fn export(app: &App, format: ExportFormat, output: Option<PathBuf>) -> Result<String, Box<dyn Error>> {
+    let contents = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&app.data())? + "\n",
+        ExportFormat::Csv => {
+            let mut csv = String::from("end_time,session_type,duration_minutes,outcome,task,project\n");
+            for session in &app.history.sessions {
+                let task = session.task.and_then(|id| app.tasks.get(id));
+                let _ = writeln!(
+                    csv,
+                    "{},{:?},{},{:?},{},{}",
+                    session.end_time.to_rfc3339(),
+                    session.session_type,
+                    session.duration,
+                    session.outcome,
+                    csv_field(task.map(|t| t.name.as_str()).unwrap_or_default()),
+                    csv_field(task.map(|t| t.project.as_str()).unwrap_or_default())
+                );
+            }
+            csv
+        }
+    };
+    match output {
+        Some(path) => {
+            fs::write(&path, contents)?;
+            Ok(String::new())
+        }
+        None => Ok(contents),
+    }
+}
+
+// Task names are typed by hand, so they may need quoting
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn task(app: &mut App, action: TaskAction) -> Result<String, Box<dyn Error>> {
+    let output = match action {
+        TaskAction::Add { name, project, estimate, pick } => {
+            if name.trim().is_empty() {
+                return Err("a task needs a name".into());
+            }
+            let id = app.tasks.add(&name, &project, estimate);
+            if pick {
+                app.tasks.active = Some(id);
+            }
+            format!("Added task #{} {}\n", id, name.trim())
+        }
+        TaskAction::List { all } => {
+            let spent = crate::tasks::spent_by_task(&app.history);
+            let mut out = String::new();
+            for task in app.tasks.list.iter().filter(|t| all || !t.done) {
+                let done = spent.get(&Some(task.id)).map(|s| s.pomodoros).unwrap_or(0);
+                let _ = write!(out, "#{} {}", task.id, task.name);
+                if !task.project.is_empty() {
+                    let _ = write!(out, " · {}", task.project);
+                }
+                let _ = match task.estimate {
+                    0 => write!(out, " · {} pomodoros", done),
+                    estimate => write!(out, " · {}/{} pomodoros", done, estimate),
+                };
+                if task.done {
+                    out.push_str(" (done)");
+                } else if app.tasks.active == Some(task.id) {
+                    out.push_str(" (current)");
+                }
+                out.push('\n');
+            }
+            if out.is_empty() {
+                out = "No tasks, add one with `taman task add <name>`\n".to_string();
+            }
+            return Ok(out);
+        }
+        TaskAction::Pick { task } if task.eq_ignore_ascii_case("none") => {
+            app.tasks.active = None;
+            "No current task\n".to_string()
+        }
+        TaskAction::Pick { task } => {
+            let found = app.tasks.find(&task).ok_or(format!("no task '{}'", task))?;
+            if found.done {
+                return Err(format!("task #{} is already done", found.id).into());
+            }
+            let (id, name) = (found.id, found.name.clone());
+            app.tasks.active = Some(id);
+            format!("Focus sessions now go to #{} {}\n", id, name)
+        }
+        TaskAction::Done { task } => {
+            let found = app.tasks.find(&task).ok_or(format!("no task '{}'", task))?;
+            let (id, name) = (found.id, found.name.clone());
+            app.tasks.set_done(id, true);
+            format!("Finished #{} {}\n", id, name)
+        }
+    };
+    save(app)?;
+    Ok(output)
+}
+
+fn config(app: &mut App, action: ConfigAction) -> Result<String, Box<dyn Error>> {
+    let settings = serde_json::to_value(&app.settings)?;
+    match action {
+        ConfigAction::Get { key: Some(key) } => {
+            let value = lookup(&settings, &key).ok_or(format!("unknown setting '{}'", key))?;
+            Ok(format!("{}\n", value))
+        }
+        ConfigAction::Get { key: None } => {
+            let mut lines = vec![];
+            flatten(&settings, String::new(), &mut lines);
+            Ok(lines.iter().map(|(key, value)| format!("{} = {}\n", key, value)).collect())
+        }
+        ConfigAction::Set { key, value } => {
+            let mut updated = settings.clone();
+            let slot = key.split('.').try_fold(&mut updated, |v, part| v.get_mut(part))
+                .ok_or(format!("unknown setting '{}'", key))?;
+            // Bare words like `Nord` are taken as strings so they don't need JSON quotes
+            *slot = serde_json::from_str(&value).unwrap_or(Value::String(value.clone()));
+            let settings: Settings = serde_json::from_value(updated)
+                .map_err(|e| format!("'{}' is not a valid value for {}: {}", value, key, e))?;
+            settings.check()?;
+            if app.themes.find(&settings.theme).is_none() {
+                let names: Vec<&str> = app.themes.list.iter().map(|t| t.name.as_str()).collect();
+                return Err(format!("unknown theme '{}', expected one of: {}", settings.theme, names.join(", ")).into());
+            }
+            app.apply_settings(settings);
+            save(app)?;
+            let saved = lookup(&serde_json::to_value(&app.settings)?, &key).unwrap_or_default();
+            Ok(format!("{} = {}\n", key, saved))
+        }
+    }
+}
+
+fn lookup(settings: &Value, key: &str) -> Option<Value> {
+    key.split('.').try_fold(settings, |v, part| v.get(part)).cloned()
+}
+
+// Nested settings such as growth rules become dotted keys
+fn flatten(value: &Value, prefix: String, lines: &mut Vec<(String, Value)>) {
+    match value.as_object() {
+        Some(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(value, key, lines);
+            }
+        }
+        None => lines.push((prefix, value.clone())),
+    }
+}
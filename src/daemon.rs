@@ -0,0 +1,352 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::App;
+use crate::events::AppEvent;
+use crate::storage::{ActiveSession, Settings};
+use crate::timer::{SessionType, TimerState};
+
+// One JSON object per line in both directions, e.g. {"cmd":"start","session":"Focus"}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    Start {
+        session: SessionType,
+        #[serde(default)]
+        auto_run_index: Option<usize>,
+    },
+    Pause,
+    Resume,
+    Stop,
+    CountGap,
+    DiscardGap,
+    EndSession,
+    Status,
+    Subscribe, // keeps the connection open for a status event on every change
+    Settings { settings: Box<Settings> },
+    Queue { auto_run: Vec<SessionType>, auto_run_index: Option<usize> },
+    Tasks { tasks: crate::tasks::Tasks },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DaemonStatus {
+    pub session_type: SessionType,
+    pub state: TimerState,
+    pub remaining_seconds: u64,
+    pub duration_seconds: u64,
+    pub active: Option<ActiveSession>,
+    pub auto_run: Vec<SessionType>,
+    pub auto_run_index: Option<usize>,
+    pub cycle_completed: u32,
+    pub settings: Settings,
+    #[serde(default)]
+    pub tasks: crate::tasks::Tasks,
+    // (sessions, plants, growth points, strikes): clients reload the saved data when it moves
+    pub progress: (usize, usize, u32, u32),
+}
+
+impl DaemonStatus {
+    fn of(app: &App) -> Self {
+        DaemonStatus {
+            session_type: app.timer.session_type,
+            state: app.timer.state,
+            remaining_seconds: app.timer.remaining_seconds,
+            duration_seconds: app.timer.duration_seconds,
+            active: app.timer.snapshot(),
+            auto_run: app.timer.auto_run.clone(),
+            auto_run_index: app.timer.auto_run_index,
+            cycle_completed: app.timer.cycle_completed,
+            settings: app.settings.clone(),
+            tasks: app.tasks.clone(),
+            progress: (app.history.sessions.len(), app.garden.completed_plants.len(), app.plant.growth_points, app.plant.strikes),
+        }
+    }
+}
+
+// Replies carry `ok`; pushes to subscribers carry `"event": "status"` instead,
+// or the event's name with its fields under `details`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Reply {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    #[serde(default)]
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<DaemonStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<AppEvent>,
+}
+
+// What a subscribed client hears from the daemon
+#[derive(Debug)]
+pub enum DaemonEvent {
+    Status(Box<DaemonStatus>),
+    App(AppEvent),
+    Closed,
+}
+
+impl Reply {
+    fn ok(app: &App) -> Self {
+        Reply { event: None, ok: true, error: None, status: Some(DaemonStatus::of(app)), details: None }
+    }
+
+    fn error(message: impl Into<String>) -> Self {
+        Reply { event: None, ok: false, error: Some(message.into()), status: None, details: None }
+    }
+
+    fn event(status: DaemonStatus) -> Self {
+        Reply { event: Some("status".to_string()), ok: true, error: None, status: Some(status), details: None }
+    }
+
+    fn app_event(event: AppEvent) -> Self {
+        Reply { event: Some(event.name().to_string()), ok: true, error: None, status: None, details: Some(event) }
+    }
+}
+
+pub fn socket_path() -> PathBuf {
+    match dirs::runtime_dir() {
+        Some(dir) => dir.join("taman.sock"),
+        None => std::env::temp_dir().join(format!("taman-{}.sock", std::env::var("USER").unwrap_or_default())),
+    }
+}
+
+fn handle(app: &mut App, request: Request) -> Reply {
+    let in_progress = matches!(app.timer.state, TimerState::Running | TimerState::Paused | TimerState::Suspended);
+    let changes = !matches!(request, Request::Status | Request::Subscribe);
+    match request {
+        Request::Start { session, auto_run_index } => {
+            app.start_session(session);
+            if auto_run_index.is_some() {
+                app.timer.auto_run_index = auto_run_index;
+            }
+        }
+        Request::Pause if app.timer.state == TimerState::Running => app.timer.pause(),
+        Request::Pause => return Reply::error("no session is running"),
+        Request::Resume if app.timer.state == TimerState::Paused => app.timer.resume(),
+        Request::Resume => return Reply::error("no session is paused"),
+        Request::Stop if in_progress => app.stop_session(),
+        Request::Stop => return Reply::error("no session in progress"),
+        Request::CountGap | Request::DiscardGap | Request::EndSession if app.timer.state != TimerState::Suspended => {
+            return Reply::error("the timer is not waiting for a suspend decision");
+        }
+        Request::CountGap => app.timer.count_gap(),
+        Request::DiscardGap => app.timer.discard_gap(),
+        Request::EndSession => app.stop_session(),
+        Request::Status | Request::Subscribe => {}
+        Request::Settings { settings } => app.apply_settings(*settings),
+        Request::Queue { auto_run, auto_run_index } => {
+            app.timer.auto_run_index = auto_run_index.filter(|&i| i < auto_run.len());
+            app.timer.auto_run = auto_run;
+        }
+        Request::Tasks { tasks } => app.tasks = tasks,
+    }
+    if changes {
+        app.save();
+    }
+    match app.status_message.take() {
+        Some(err) => Reply::error(err),
+        None => Reply::ok(app),
+    }
+}
+
+#[cfg(unix)]
+pub use unix::{run, DaemonClient};
+
+#[cfg(not(unix))]
+pub use fallback::{run, DaemonClient};
+
+#[cfg(unix)]
+mod unix {
+    use std::error::Error;
+    use std::fs;
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::{handle, socket_path, DaemonEvent, DaemonStatus, Reply, Request};
+    use crate::app::App;
+
+    // Owns the App and its tick loop; every connection gets a reader thread that
+    // hands lines to this loop, so the App itself is only touched from one place
+    pub fn run() -> Result<(), Box<dyn Error>> {
+        let path = socket_path();
+        if UnixStream::connect(&path).is_ok() {
+            return Err(format!("a daemon is already listening on {}", path.display()).into());
+        }
+        // Left behind by a daemon that didn't shut down cleanly
+        let _ = fs::remove_file(&path);
+        // Held until the daemon exits, so neither a TUI nor the CLI saves over it
+        let _lock = crate::storage::lock_data()?
+            .ok_or("taman is open in another terminal; close it before starting the daemon")?;
+
+        let mut app = App::new();
+        // Attached clients take care of terminal notifications
+        app.notifiers.terminal = false;
+        if let Some(notice) = app.status_message.take() {
+            eprintln!("taman daemon: {}", notice);
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        eprintln!("taman daemon: listening on {}", path.display());
+
+        let (lines_tx, lines_rx) = mpsc::channel::<(String, UnixStream)>();
+        let mut subscribers: Vec<UnixStream> = vec![];
+        let mut last_event = String::new();
+        loop {
+            while let Ok((stream, _)) = listener.accept() {
+                read_requests(stream, lines_tx.clone());
+            }
+            while let Ok((line, mut client)) = lines_rx.try_recv() {
+                let reply = match serde_json::from_str::<Request>(&line) {
+                    Ok(Request::Subscribe) => {
+                        if let Ok(subscriber) = client.try_clone() {
+                            subscribers.push(subscriber);
+                        }
+                        Reply::ok(&app)
+                    }
+                    Ok(request) => handle(&mut app, request),
+                    Err(err) => Reply::error(format!("bad request: {}", err)),
+                };
+                let _ = send(&mut client, &reply);
+            }
+
+            app.tick();
+            for event in app.dispatch_events() {
+                let reply = Reply::app_event(event);
+                subscribers.retain_mut(|subscriber| send(subscriber, &reply).is_ok());
+            }
+            if let Some(err) = app.status_message.take() {
+                eprintln!("taman daemon: {}", err);
+            }
+            let status = DaemonStatus::of(&app);
+            let event = serde_json::to_string(&status)?;
+            if event != last_event {
+                let reply = Reply::event(status);
+                subscribers.retain_mut(|subscriber| send(subscriber, &reply).is_ok());
+                last_event = event;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    fn read_requests(stream: UnixStream, lines: Sender<(String, UnixStream)>) {
+        // A client that stops reading must not stall the tick loop
+        let _ = stream.set_nonblocking(false);
+        let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+        thread::spawn(move || {
+            let Ok(writer) = stream.try_clone() else { return };
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let Ok(writer) = writer.try_clone() else { break };
+                if lines.send((line, writer)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    fn send(stream: &mut UnixStream, reply: &Reply) -> io::Result<()> {
+        let line = serde_json::to_string(reply).map_err(io::Error::other)?;
+        writeln!(stream, "{}", line)
+    }
+
+    #[derive(Debug)]
+    pub struct DaemonClient {
+        writer: UnixStream,
+        replies: Receiver<Reply>,
+        events: Receiver<DaemonEvent>,
+        pub last: DaemonStatus,
+    }
+
+    impl DaemonClient {
+        // Subscribes straight away, so the caller starts from the daemon's state
+        pub fn connect() -> Option<Self> {
+            let stream = UnixStream::connect(socket_path()).ok()?;
+            let reader = stream.try_clone().ok()?;
+            let (replies_tx, replies) = mpsc::channel();
+            let (events_tx, events) = mpsc::channel();
+            thread::spawn(move || {
+                for line in BufReader::new(reader).lines() {
+                    let Ok(line) = line else { break };
+                    let Ok(reply) = serde_json::from_str::<Reply>(&line) else { continue };
+                    if reply.event.is_none() {
+                        if replies_tx.send(reply).is_err() {
+                            return;
+                        }
+                        continue;
+                    }
+                    let event = match (reply.status, reply.details) {
+                        (Some(status), _) => DaemonEvent::Status(Box::new(status)),
+                        (None, Some(event)) => DaemonEvent::App(event),
+                        (None, None) => continue,
+                    };
+                    if events_tx.send(event).is_err() {
+                        return;
+                    }
+                }
+                let _ = events_tx.send(DaemonEvent::Closed);
+            });
+            let mut writer = stream;
+            let last = exchange(&mut writer, &replies, &Request::Subscribe).ok()?;
+            Some(DaemonClient { writer, replies, events, last })
+        }
+
+        pub fn request(&mut self, request: &Request) -> Result<DaemonStatus, String> {
+            exchange(&mut self.writer, &self.replies, request)
+        }
+
+        pub fn events(&self) -> Vec<DaemonEvent> {
+            self.events.try_iter().collect()
+        }
+    }
+
+    fn exchange(writer: &mut UnixStream, replies: &Receiver<Reply>, request: &Request) -> Result<DaemonStatus, String> {
+        let line = serde_json::to_string(request).map_err(|e| e.to_string())?;
+        writeln!(writer, "{}", line).map_err(|e| format!("the daemon can't be reached ({})", e))?;
+        let reply = replies
+            .recv_timeout(Duration::from_secs(2))
+            .map_err(|_| "the daemon did not answer".to_string())?;
+        match (reply.ok, reply.status) {
+            (true, Some(status)) => Ok(status),
+            _ => Err(reply.error.unwrap_or("the request failed".to_string())),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod fallback {
+    use std::error::Error;
+
+    use super::{DaemonEvent, DaemonStatus, Request};
+
+    pub fn run() -> Result<(), Box<dyn Error>> {
+        Err("the daemon needs Unix domain sockets, which this platform doesn't have".into())
+    }
+
+    #[derive(Debug)]
+    pub struct DaemonClient {
+        pub last: DaemonStatus,
+    }
+
+    impl DaemonClient {
+        pub fn connect() -> Option<Self> {
+            None
+        }
+
+        pub fn request(&mut self, _request: &Request) -> Result<DaemonStatus, String> {
+            Err("the daemon is not supported on this platform".to_string())
+        }
+
+        pub fn events(&self) -> Vec<DaemonEvent> {
+            vec![]
+        }
+    }
+}
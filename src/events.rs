@@ -0,0 +1,50 @@
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::timer::SessionType;
+
+// Something that happened to the timer or the garden. The App queues these
+// while it works and hands them out once per tick.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AppEvent {
+    SessionStart {
+        session_type: SessionType,
+        duration_minutes: u64,
+        started_at: DateTime<Local>,
+    },
+    SessionComplete {
+        session_type: SessionType,
+        duration_minutes: u64,
+        end_time: DateTime<Local>,
+    },
+    SessionAbandoned {
+        session_type: SessionType,
+        duration_minutes: u64,
+        elapsed_minutes: u64,
+        end_time: DateTime<Local>,
+    },
+    PlantComplete {
+        species: String,
+        species_name: String,
+        sessions: u32,
+        completed_at: DateTime<Local>,
+    },
+    StreakBroken {
+        streak_days: u32,
+        last_active_day: Option<NaiveDate>,
+    },
+}
+
+impl AppEvent {
+    // Same as the "event" field in the JSON
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEvent::SessionStart { .. } => "session_start",
+            AppEvent::SessionComplete { .. } => "session_complete",
+            AppEvent::SessionAbandoned { .. } => "session_abandoned",
+            AppEvent::PlantComplete { .. } => "plant_complete",
+            AppEvent::StreakBroken { .. } => "streak_broken",
+        }
+    }
+}
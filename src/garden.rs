@@ -5,6 +5,14 @@ use crate::plant::Plant;
 pub struct CompletedPlant {
     pub plant: Plant,
     pub completed_at: DateTime<Utc>,
+    #[serde(default)]
+    pub withered: bool,
+}
+
+impl CompletedPlant {
+    pub fn icon(&self) -> &'static str {
+        if self.withered { "🥀" } else { self.plant.species().icon() }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,23 +39,37 @@ impl Garden {
         }
     }
 
-    pub fn add_completed_plant(&mut self, plant: Plant) {
+    pub fn add_completed_plant(&mut self, plant: Plant, completed_at: DateTime<Utc>) {
         let completed = CompletedPlant {
             plant,
-            completed_at: Utc::now(),
+            completed_at,
+            withered: false,
         };
         self.completed_plants.push(completed);
     }
 
+    pub fn add_withered_plant(&mut self, plant: Plant, died_at: DateTime<Utc>) {
+        let withered = CompletedPlant {
+            plant,
+            completed_at: died_at,
+            withered: true,
+        };
+        self.completed_plants.push(withered);
+    }
+
 
 
     pub fn total_completed(&self) -> usize {
-        self.completed_plants.len()
+        self.completed_plants.iter().filter(|p| !p.withered).count()
+    }
+
+    pub fn total_withered(&self) -> usize {
+        self.completed_plants.iter().filter(|p| p.withered).count()
     }
 
-    pub fn update_streaks(&mut self, recent_sessions: &[(DateTime<chrono::Local>, u32)]) {
+    pub fn update_streaks(&mut self, active_days: &[NaiveDate]) {
         use chrono::Local;
-        if recent_sessions.is_empty() {
+        if active_days.is_empty() {
             self.current_streak = 0;
             self.longest_streak = 0;
             self.current_streak_start_date = None;
@@ -56,14 +78,14 @@ impl Garden {
             self.longest_streak_dates = vec![];
             return;
         }
-        let mut dates: Vec<NaiveDate> = recent_sessions.iter().map(|(d, _)| d.date_naive()).collect();
+        let mut dates: Vec<NaiveDate> = active_days.to_vec();
         dates.sort();
         dates.dedup();
         let today = Local::now().date_naive();
         let yesterday = today - Duration::days(1);
         let last_date = *dates.last().unwrap();
-        let mut current_streak = 0;
-        let mut current_dates = vec![];
+        let current_streak;
+        let current_dates;
         if last_date == today || last_date == yesterday {
             // find the group ending with last_date
             let mut i = dates.len() - 1;
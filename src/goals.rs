@@ -0,0 +1,135 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{group, History, Period, SessionKind};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GoalUnit {
+    #[default]
+    Sessions,
+    Minutes,
+}
+
+impl GoalUnit {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GoalUnit::Sessions => "focus sessions",
+            GoalUnit::Minutes => "focus minutes",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct GoalSettings {
+    pub unit: GoalUnit,
+    pub daily: u64, // 0 turns the goal off
+    pub weekly: u64,
+    pub streak_needs_goal: bool, // streaks only count days the daily goal was met
+}
+
+impl GoalSettings {
+    // Completed focus sessions or focus minutes, one entry per day with any
+    pub fn daily_values(&self, history: &History) -> Vec<(NaiveDate, u64)> {
+        match self.unit {
+            GoalUnit::Sessions => history.daily_sessions(SessionKind::Focus),
+            GoalUnit::Minutes => history.daily_minutes(SessionKind::Focus),
+        }
+    }
+
+    // Progress over a stretch of days, both ends included
+    pub fn progress(&self, history: &History, from: NaiveDate, to: NaiveDate) -> u64 {
+        self.daily_values(history).iter().filter(|(d, _)| *d >= from && *d <= to).map(|(_, v)| v).sum()
+    }
+
+    pub fn met_days(&self, history: &History) -> Vec<NaiveDate> {
+        if self.daily == 0 {
+            return vec![];
+        }
+        self.daily_values(history).into_iter().filter(|(_, v)| *v >= self.daily).map(|(d, _)| d).collect()
+    }
+
+    // Switching unit keeps the goals about the same size, counting a session as `focus_minutes`
+    pub fn switch_unit(&mut self, focus_minutes: u64) {
+        let focus_minutes = focus_minutes.max(1);
+        match self.unit {
+            GoalUnit::Sessions => {
+                self.unit = GoalUnit::Minutes;
+                self.daily *= focus_minutes;
+                self.weekly *= focus_minutes;
+            }
+            GoalUnit::Minutes => {
+                self.unit = GoalUnit::Sessions;
+                self.daily = self.daily.div_ceil(focus_minutes);
+                self.weekly = self.weekly.div_ceil(focus_minutes);
+            }
+        }
+    }
+
+    // Days that keep a streak going: any day with a session, unless streaks need the daily goal
+    pub fn streak_days(&self, history: &History) -> Vec<NaiveDate> {
+        if self.streak_needs_goal && self.daily > 0 {
+            self.met_days(history)
+        } else {
+            history.active_days()
+        }
+    }
+
+    // (periods the goal was met, periods) for the `count` days or weeks up to today
+    pub fn hits(&self, history: &History, period: Period, count: i64, today: NaiveDate) -> (usize, usize) {
+        let goal = match period {
+            Period::Week => self.weekly,
+            _ => self.daily,
+        };
+        let from = period.shift(period.start(today), 1 - count);
+        let values = group(&self.daily_values(history), period, from, today);
+        (values.iter().filter(|(_, v)| goal > 0 && *v >= goal).count(), values.len())
+    }
+
+    // Days in a row the daily goal was met. Today only breaks the run once it's over.
+    pub fn days_in_a_row(&self, history: &History, today: NaiveDate) -> u32 {
+        let met = self.met_days(history);
+        let mut day = if met.contains(&today) { today } else { today - Duration::days(1) };
+        let mut run = 0;
+        while met.contains(&day) {
+            run += 1;
+            day -= Duration::days(1);
+        }
+        run
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{SessionLog, SessionOutcome};
+    use crate::timer::SessionType;
+    use chrono::{Local, TimeZone};
+
+    fn focus(d: u32, duration: u64) -> SessionLog {
+        SessionLog {
+            session_type: SessionType::Focus,
+            duration,
+            end_time: Local.with_ymd_and_hms(2026, 10, d, 12, 0, 0).unwrap(),
+            outcome: SessionOutcome::Completed,
+            task: None,
+        }
+    }
+
+    #[test]
+    fn counts_goal_days_and_weeks_and_switches_unit() {
+        // Mon 12th: 2 sessions, Tue 13th: 1, Wed 14th: 2, Thu 15th (today): none yet
+        let history = History::new(vec![focus(12, 25), focus(12, 25), focus(13, 25), focus(14, 25), focus(14, 25)]);
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let mut goals = GoalSettings { unit: GoalUnit::Sessions, daily: 2, weekly: 6, streak_needs_goal: true };
+        assert_eq!(goals.hits(&history, Period::Day, 7, today), (2, 7));
+        assert_eq!(goals.hits(&history, Period::Week, 2, today), (0, 2));
+        assert_eq!(goals.days_in_a_row(&history, today), 1);
+        assert_eq!(goals.streak_days(&history).len(), 2);
+        goals.switch_unit(25);
+        assert_eq!((goals.unit, goals.daily, goals.weekly), (GoalUnit::Minutes, 50, 150));
+        assert_eq!(goals.progress(&history, Period::Week.start(today), today), 125);
+        goals.daily = 0;
+        assert_eq!(goals.streak_days(&history).len(), 3);
+    }
+}
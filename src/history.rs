@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveTime, Timelike, Weekday};
+
+use crate::garden::CompletedPlant;
+use crate::storage::{SessionLog, SessionOutcome};
+use crate::timer::SessionType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionKind {
+    All,
+    Focus,
+    Break,
+}
+
+impl SessionKind {
+    pub fn matches(&self, session_type: SessionType) -> bool {
+        match self {
+            SessionKind::All => true,
+            SessionKind::Focus => session_type == SessionType::Focus,
+            SessionKind::Break => matches!(session_type, SessionType::ShortBreak | SessionType::LongBreak),
+        }
+    }
+}
+
+// How the Stats tab groups its series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    #[default]
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    pub const ALL: [Period; 4] = [Period::Day, Period::Week, Period::Month, Period::Year];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+            Period::Year => "year",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Period::Day => "Day",
+            Period::Week => "Week",
+            Period::Month => "Month",
+            Period::Year => "Year",
+        }
+    }
+
+    // First day of the period the date falls in; weeks start on Monday
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => date.week(Weekday::Mon).first_day(),
+            Period::Month => date.with_day(1).unwrap_or(date),
+            Period::Year => date.with_ordinal(1).unwrap_or(date),
+        }
+    }
+
+    // Moves the start of a period by whole periods, back for negative counts
+    pub fn shift(&self, start: NaiveDate, count: i64) -> NaiveDate {
+        let months = |n: i64| Months::new(n.unsigned_abs() as u32);
+        let shifted = match self {
+            Period::Day => start.checked_add_signed(chrono::Duration::days(count)),
+            Period::Week => start.checked_add_signed(chrono::Duration::weeks(count)),
+            Period::Month if count < 0 => start.checked_sub_months(months(count)),
+            Period::Month => start.checked_add_months(months(count)),
+            Period::Year if count < 0 => start.checked_sub_months(months(count * 12)),
+            Period::Year => start.checked_add_months(months(count * 12)),
+        };
+        shifted.unwrap_or(start)
+    }
+
+    // The last day of the period starting on `start`
+    pub fn end(&self, start: NaiveDate) -> NaiveDate {
+        self.shift(start, 1).pred_opt().unwrap_or(start)
+    }
+
+    // Periods from the one holding `from` up to the one holding `to`, both included
+    pub fn count(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        let (from, to) = (self.start(from), self.start(to));
+        match self {
+            Period::Day => (to - from).num_days() + 1,
+            Period::Week => (to - from).num_weeks() + 1,
+            Period::Month => (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64 + 1,
+            Period::Year => (to.year() - from.year()) as i64 + 1,
+        }
+    }
+}
+
+// Sums a daily series into one entry per period from the period of `from` to the
+// period of `to`, keyed by the period's first day. Periods without any entry are 0.
+pub fn group(series: &[(NaiveDate, u64)], period: Period, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, u64)> {
+    let mut periods: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+    let mut start = period.start(from);
+    while start <= to {
+        periods.insert(start, 0);
+        start = period.shift(start, 1);
+    }
+    for (date, value) in series {
+        if let Some(total) = periods.get_mut(&period.start(*date)) {
+            *total += value;
+        }
+    }
+    periods.into_iter().collect()
+}
+
+// The session log is the single source of truth: every total, daily series and
+// streak is computed from it on demand rather than kept up to date by hand
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub sessions: Vec<SessionLog>, // oldest first
+}
+
+impl History {
+    pub fn new(mut sessions: Vec<SessionLog>) -> Self {
+        sessions.sort_by_key(|s| s.end_time);
+        History { sessions }
+    }
+
+    pub fn record(&mut self, session: SessionLog) {
+        let index = self.sessions.partition_point(|s| s.end_time <= session.end_time);
+        self.sessions.insert(index, session);
+    }
+
+    pub fn completed(&self, kind: SessionKind) -> impl DoubleEndedIterator<Item = &SessionLog> {
+        self.sessions.iter().filter(move |s| s.outcome == SessionOutcome::Completed && kind.matches(s.session_type))
+    }
+
+    pub fn total_sessions(&self, kind: SessionKind) -> u32 {
+        self.completed(kind).count() as u32
+    }
+
+    pub fn total_minutes(&self, kind: SessionKind) -> u64 {
+        self.completed(kind).map(|s| s.duration).sum()
+    }
+
+    // One (date, count) entry per day with at least one session
+    pub fn daily_sessions(&self, kind: SessionKind) -> Vec<(NaiveDate, u64)> {
+        self.daily(kind, |_| 1)
+    }
+
+    pub fn daily_minutes(&self, kind: SessionKind) -> Vec<(NaiveDate, u64)> {
+        self.daily(kind, |s| s.duration)
+    }
+
+    // Days that count towards a streak
+    pub fn active_days(&self) -> Vec<NaiveDate> {
+        self.daily_sessions(SessionKind::All).into_iter().map(|(d, _)| d).collect()
+    }
+
+    // Focus minutes per hour of the day, a session split over the hours it ran in
+    pub fn focus_minutes_by_hour(&self) -> [u64; 24] {
+        let mut seconds = [0u64; 24];
+        for session in self.completed(SessionKind::Focus) {
+            let mut time = started_at(session);
+            while time < session.end_time {
+                let hour_start = time.with_minute(0).and_then(|t| t.with_second(0)).and_then(|t| t.with_nanosecond(0)).unwrap_or(time);
+                let next = (hour_start + Duration::hours(1)).min(session.end_time);
+                seconds[time.hour() as usize] += (next - time).num_seconds().max(0) as u64;
+                time = next;
+            }
+        }
+        seconds.map(|s| s / 60)
+    }
+
+    // Focus minutes per weekday, Monday first, by the day a session started
+    pub fn focus_minutes_by_weekday(&self) -> [u64; 7] {
+        let mut minutes = [0u64; 7];
+        for session in self.completed(SessionKind::Focus) {
+            minutes[started_at(session).weekday().num_days_from_monday() as usize] += session.duration;
+        }
+        minutes
+    }
+
+    // The `hours` long stretch of the day with the most focus minutes, as (first hour, minutes).
+    // It may run past midnight.
+    pub fn best_focus_window(&self, hours: usize) -> Option<(usize, u64)> {
+        let by_hour = self.focus_minutes_by_hour();
+        (0..24)
+            .map(|start| (start, (start..start + hours).map(|h| by_hour[h % 24]).sum::<u64>()))
+            .filter(|(_, minutes)| *minutes > 0)
+            .max_by_key(|(start, minutes)| (*minutes, std::cmp::Reverse(*start)))
+    }
+
+    // Mean time of day that focus sessions started, and the same for each day's first one
+    pub fn average_focus_starts(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let starts: Vec<DateTime<Local>> = self.completed(SessionKind::Focus).map(started_at).collect();
+        let mut firsts: BTreeMap<NaiveDate, NaiveTime> = BTreeMap::new();
+        for start in &starts {
+            let first = firsts.entry(start.date_naive()).or_insert(start.time());
+            *first = (*first).min(start.time());
+        }
+        let mean = |times: Vec<NaiveTime>| {
+            let seconds: u64 = times.iter().map(|t| t.num_seconds_from_midnight() as u64).sum();
+            NaiveTime::from_num_seconds_from_midnight_opt((seconds / times.len().max(1) as u64) as u32, 0)
+        };
+        if starts.is_empty() {
+            return None;
+        }
+        Some((mean(starts.iter().map(|s| s.time()).collect())?, mean(firsts.into_values().collect())?))
+    }
+
+    // (completed, started) focus sessions for each part of the day, by start time
+    pub fn focus_completion_by_time_of_day(&self) -> [(&'static str, u32, u32); 4] {
+        let mut parts = [("Night (00-06)", 0, 0), ("Morning (06-12)", 0, 0), ("Afternoon (12-18)", 0, 0), ("Evening (18-24)", 0, 0)];
+        for session in self.sessions.iter().filter(|s| s.session_type == SessionType::Focus) {
+            let part = &mut parts[started_at(session).hour() as usize / 6];
+            part.2 += 1;
+            if session.outcome == SessionOutcome::Completed {
+                part.1 += 1;
+            }
+        }
+        parts
+    }
+
+    fn daily(&self, kind: SessionKind, value: impl Fn(&SessionLog) -> u64) -> Vec<(NaiveDate, u64)> {
+        let mut days: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+        for session in self.completed(kind) {
+            *days.entry(session.end_time.date_naive()).or_default() += value(session);
+        }
+        days.into_iter().collect()
+    }
+}
+
+// Sessions only keep their end, so the start is worked back from the length
+pub fn started_at(session: &SessionLog) -> DateTime<Local> {
+    session.end_time - Duration::minutes(session.duration as i64)
+}
+
+pub fn daily_plants(plants: &[CompletedPlant]) -> Vec<(NaiveDate, u64)> {
+    let mut days: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+    for plant in plants.iter().filter(|p| !p.withered) {
+        *days.entry(plant.completed_at.with_timezone(&Local).date_naive()).or_default() += 1;
+    }
+    days.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn groups_daily_series_into_periods_with_empty_ones_kept() {
+        let series = vec![(day(2025, 12, 31), 2), (day(2026, 1, 5), 1), (day(2026, 1, 11), 3), (day(2026, 3, 2), 4)];
+        let weeks = group(&series, Period::Week, day(2025, 12, 31), day(2026, 1, 14));
+        assert_eq!(weeks, vec![(day(2025, 12, 29), 2), (day(2026, 1, 5), 4), (day(2026, 1, 12), 0)]);
+        let months = group(&series, Period::Month, day(2025, 12, 31), day(2026, 3, 2));
+        assert_eq!(months, vec![(day(2025, 12, 1), 2), (day(2026, 1, 1), 4), (day(2026, 2, 1), 0), (day(2026, 3, 1), 4)]);
+        assert_eq!(Period::Month.count(day(2025, 12, 31), day(2026, 3, 2)), 4);
+        assert_eq!(Period::Year.shift(day(2026, 1, 1), -2), day(2024, 1, 1));
+        assert_eq!(Period::Month.end(day(2026, 2, 1)), day(2026, 2, 28));
+    }
+
+    #[test]
+    fn spreads_focus_over_the_hours_it_ran_in() {
+        use chrono::TimeZone;
+        let session = |h: u32, m: u32, duration: u64, outcome: SessionOutcome| SessionLog {
+            session_type: SessionType::Focus,
+            duration,
+            end_time: Local.with_ymd_and_hms(2026, 10, 12, h, m, 0).unwrap(),
+            outcome,
+            task: None,
+        };
+        let history = History::new(vec![
+            session(10, 5, 25, SessionOutcome::Completed),
+            session(10, 50, 25, SessionOutcome::Completed),
+            session(23, 0, 10, SessionOutcome::Abandoned),
+        ]);
+        let by_hour = history.focus_minutes_by_hour();
+        assert_eq!((by_hour[9], by_hour[10], by_hour[22]), (20, 30, 0));
+        assert_eq!(history.best_focus_window(2), Some((9, 50)));
+        assert_eq!(history.focus_minutes_by_weekday()[0], 50);
+        let (any, first) = history.average_focus_starts().unwrap();
+        assert_eq!((any.to_string(), first.to_string()), ("10:02:30".to_string(), "09:40:00".to_string()));
+        assert_eq!(history.focus_completion_by_time_of_day()[1], ("Morning (06-12)", 2, 2));
+        assert_eq!(history.focus_completion_by_time_of_day()[3], ("Evening (18-24)", 0, 1));
+    }
+}
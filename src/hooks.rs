@@ -0,0 +1,203 @@
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::events::AppEvent;
+
+// Shell commands run when an event happens, e.g.
+// "on_session_start": "slack-status focus" or "on_session_complete": "cat >> ~/focus.log"
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct HookSettings {
+    pub on_session_start: Option<String>,
+    pub on_session_complete: Option<String>,
+    pub on_session_abandoned: Option<String>,
+    pub on_plant_complete: Option<String>,
+    pub on_streak_broken: Option<String>,
+    pub timeout_secs: u64, // a hook still running after this is killed
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        HookSettings {
+            on_session_start: None,
+            on_session_complete: None,
+            on_session_abandoned: None,
+            on_plant_complete: None,
+            on_streak_broken: None,
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl HookSettings {
+    fn command(&self, event: &AppEvent) -> Option<&str> {
+        let command = match event {
+            AppEvent::SessionStart { .. } => &self.on_session_start,
+            AppEvent::SessionComplete { .. } => &self.on_session_complete,
+            AppEvent::SessionAbandoned { .. } => &self.on_session_abandoned,
+            AppEvent::PlantComplete { .. } => &self.on_plant_complete,
+            AppEvent::StreakBroken { .. } => &self.on_streak_broken,
+        };
+        command.as_deref().filter(|c| !c.trim().is_empty())
+    }
+}
+
+// Every hook gets its own thread, so a slow script never holds up the timer.
+// Failures come back as messages for the footer.
+#[derive(Debug)]
+pub struct Hooks {
+    errors_tx: Sender<String>,
+    errors: Receiver<String>,
+    running: Vec<JoinHandle<()>>,
+}
+
+impl Hooks {
+    pub fn new() -> Self {
+        let (errors_tx, errors) = mpsc::channel();
+        Hooks { errors_tx, errors, running: vec![] }
+    }
+
+    pub fn run(&mut self, settings: &HookSettings, event: &AppEvent) {
+        let Some(command) = settings.command(event) else { return };
+        let name = format!("on_{}", event.name());
+        let command = command.to_string();
+        let json = serde_json::to_value(event).unwrap_or(Value::Null);
+        let timeout = Duration::from_secs(settings.timeout_secs.max(1));
+        let errors = self.errors_tx.clone();
+        self.running.retain(|hook| !hook.is_finished());
+        self.running.push(thread::spawn(move || {
+            if let Err(err) = run_hook(&command, &json, timeout) {
+                let _ = errors.send(format!("Hook {} failed: {}", name, err));
+            }
+        }));
+    }
+
+    pub fn errors(&self) -> Vec<String> {
+        self.errors.try_iter().collect()
+    }
+
+    // Lets hooks that are still running finish (or time out) before the process exits
+    pub fn wait(&mut self) {
+        for hook in self.running.drain(..) {
+            let _ = hook.join();
+        }
+    }
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Hooks::new()
+    }
+}
+
+fn run_hook(command: &str, json: &Value, timeout: Duration) -> Result<(), String> {
+    #[cfg(unix)]
+    let mut process = Command::new("sh");
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // In a group of its own, so a timeout also stops whatever the hook started
+        process.arg("-c").arg(command).process_group(0);
+    }
+    #[cfg(not(unix))]
+    let mut process = Command::new("cmd");
+    #[cfg(not(unix))]
+    process.arg("/C").arg(command);
+
+    // stdout would draw over the TUI, so only stderr is kept, for the error message
+    let mut child = process
+        .envs(environment(json))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("couldn't start it ({})", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that doesn't read its input closes the pipe early, which is fine
+        let _ = writeln!(stdin, "{}", json);
+    }
+    // Read on the side: a chatty hook would otherwise block on a full pipe
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+    if let Some(mut stderr) = child.stderr.take() {
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            let _ = stderr_tx.send(output);
+        });
+    }
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                kill_group(&mut child);
+                let _ = child.wait();
+                return Err(format!("timed out after {}s", timeout.as_secs()));
+            }
+            None => thread::sleep(Duration::from_millis(20)),
+        }
+    };
+    if status.success() {
+        return Ok(());
+    }
+    // Something the hook left running in the background can keep stderr open
+    let output = stderr_rx.recv_timeout(Duration::from_millis(500)).unwrap_or_default();
+    match output.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => Err(format!("{} ({})", line.trim(), status)),
+        None => Err(status.to_string()),
+    }
+}
+
+#[cfg(unix)]
+fn kill_group(child: &mut std::process::Child) {
+    // The group id is the id of `sh`, which leads it
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_group(child: &mut std::process::Child) {
+    let _ = child.kill();
+}
+
+// TAMAN_EVENT=session_complete, TAMAN_SESSION_TYPE=Focus, TAMAN_DURATION_MINUTES=25, ...
+fn environment(json: &Value) -> Vec<(String, String)> {
+    let Some(fields) = json.as_object() else { return vec![] };
+    fields.iter().map(|(key, value)| {
+        let value = match value {
+            Value::String(text) => text.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        (format!("TAMAN_{}", key.to_uppercase()), value)
+    }).collect()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    // Looks the process up in /proc
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn a_timeout_stops_what_the_hook_started() {
+        let pid_file = std::env::temp_dir().join(format!("taman-hook-{}.pid", std::process::id()));
+        let command = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+        let result = run_hook(&command, &Value::Null, Duration::from_secs(1));
+        assert_eq!(result, Err("timed out after 1s".to_string()));
+        let pid = std::fs::read_to_string(&pid_file).unwrap().trim().to_string();
+        let _ = std::fs::remove_file(&pid_file);
+        // Gone, or a zombie waiting to be reaped now that its parent is dead
+        thread::sleep(Duration::from_millis(200));
+        let state = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap_or_default();
+        assert!(state.is_empty() || state.contains(") Z "), "{}", state);
+    }
+}
@@ -1,8 +1,8 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::timer::SessionType;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputAction {
-    Tab(u8), // 1-4...
+    Tab(u8), // 1-5...
     Left,
     Right,
     Up,
@@ -12,24 +12,60 @@ pub enum InputAction {
     Quit,
     Enter,
     Delete,
+    CountGap,
+    DiscardGap,
+    EndSession,
+    PrevPeriod,
+    NextPeriod,
+    Reset,
+    Help,
+    Palette,
+    Tasks,
+    TogglePause,
+    StartSession(SessionType),
+    ClearQueue,
+    SetTheme(usize), // index into App::themes
+    Export(crate::cli::ExportFormat),
 }
 
-pub fn handle_key(key: KeyEvent) -> Option<InputAction> {
-    match key.code {
-        KeyCode::Char('1') => Some(InputAction::Tab(1)),
-        KeyCode::Char('2') => Some(InputAction::Tab(2)),
-        KeyCode::Char('3') => Some(InputAction::Tab(3)),
-        KeyCode::Char('4') => Some(InputAction::Tab(4)),
-        KeyCode::Left => Some(InputAction::Left),
-        KeyCode::Right => Some(InputAction::Right),
-        KeyCode::Up => Some(InputAction::Up),
-        KeyCode::Down => Some(InputAction::Down),
-        KeyCode::Char(' ') => Some(InputAction::Space),
-        KeyCode::Char('s') | KeyCode::Char('S') => Some(InputAction::Stop),
-        KeyCode::Char('q') | KeyCode::Char('Q') => Some(InputAction::Quit),
-        KeyCode::Enter => Some(InputAction::Enter),
-        KeyCode::Delete => Some(InputAction::Delete),
-        KeyCode::Backspace => Some(InputAction::Delete),
-        _ => None,
+// Names used in keys.toml
+pub const ACTIONS: [(&str, InputAction); 28] = [
+    ("timer_tab", InputAction::Tab(1)),
+    ("plant_tab", InputAction::Tab(2)),
+    ("stats_tab", InputAction::Tab(3)),
+    ("settings_tab", InputAction::Tab(4)),
+    ("garden_tab", InputAction::Tab(5)),
+    ("left", InputAction::Left),
+    ("right", InputAction::Right),
+    ("up", InputAction::Up),
+    ("down", InputAction::Down),
+    ("start_pause", InputAction::Space),
+    ("stop", InputAction::Stop),
+    ("quit", InputAction::Quit),
+    ("enter", InputAction::Enter),
+    ("delete", InputAction::Delete),
+    ("count_gap", InputAction::CountGap),
+    ("discard_gap", InputAction::DiscardGap),
+    ("end_session", InputAction::EndSession),
+    ("prev_period", InputAction::PrevPeriod),
+    ("next_period", InputAction::NextPeriod),
+    ("reset", InputAction::Reset),
+    ("help", InputAction::Help),
+    ("palette", InputAction::Palette),
+    ("tasks", InputAction::Tasks),
+    ("pause_resume", InputAction::TogglePause),
+    ("start_focus", InputAction::StartSession(SessionType::Focus)),
+    ("start_short_break", InputAction::StartSession(SessionType::ShortBreak)),
+    ("start_long_break", InputAction::StartSession(SessionType::LongBreak)),
+    ("clear_queue", InputAction::ClearQueue),
+];
+
+impl InputAction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        ACTIONS.iter().find(|(n, _)| *n == name).map(|(_, action)| *action)
+    }
+
+    pub fn name(&self) -> &'static str {
+        ACTIONS.iter().find(|(_, action)| action == self).map(|(n, _)| *n).unwrap_or("unknown")
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,347 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::input::{InputAction, ACTIONS};
+
+// The keys taman has always used
+const DEFAULT_KEYS: [(&str, &[&str]); 28] = [
+    ("timer_tab", &["1"]),
+    ("plant_tab", &["2"]),
+    ("stats_tab", &["3"]),
+    ("settings_tab", &["4"]),
+    ("garden_tab", &["5"]),
+    ("left", &["left"]),
+    ("right", &["right"]),
+    ("up", &["up"]),
+    ("down", &["down"]),
+    ("start_pause", &["space"]),
+    ("stop", &["s", "S"]),
+    ("quit", &["q", "Q"]),
+    ("enter", &["enter"]),
+    ("delete", &["delete", "backspace"]),
+    ("count_gap", &["c", "C"]),
+    ("discard_gap", &["d", "D"]),
+    ("end_session", &["e", "E"]),
+    ("prev_period", &["["]),
+    ("next_period", &["]"]),
+    ("reset", &["r", "R"]),
+    ("help", &["?"]),
+    ("palette", &[":", "ctrl+p"]),
+    ("tasks", &["t", "T"]),
+    ("pause_resume", &[]),
+    ("start_focus", &[]),
+    ("start_short_break", &[]),
+    ("start_long_break", &[]),
+    ("clear_queue", &[]),
+];
+
+// Added in front of the defaults, so the arrows keep working
+const VIM_KEYS: [(&str, &[&str]); 4] = [
+    ("left", &["h"]),
+    ("down", &["j"]),
+    ("up", &["k"]),
+    ("right", &["l"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    // Shift is already in the case of a letter, so it's dropped for characters
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        KeyChord { code, modifiers }
+    }
+
+    // "q", "S", "space", "ctrl+p", "alt+left", "shift+tab", "f1", ...
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (prefix, key) = match text.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"), // "ctrl++"
+            None if text == "+" => ("", "+"),
+            None => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for part in prefix.split('+').filter(|p| !p.is_empty()) {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier `{}` in `{}`", part, text)),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=24).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{}`", text)),
+                },
+            },
+        };
+        let modifiers = match code {
+            KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Ok(KeyChord::new(code, modifiers))
+    }
+
+    // As shown in the footer: "S", "Space", "Ctrl+P", "←"
+    pub fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_uppercase().to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Insert => "Ins".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("Shift+");
+        }
+        label + &key
+    }
+
+    // Close to how keys.toml spells it, where `s` and `S` are different keys
+    fn spelling(&self) -> String {
+        match self.code {
+            KeyCode::Char(c) if c != ' ' && self.modifiers.is_empty() => c.to_string(),
+            _ => self.label().to_lowercase(),
+        }
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        KeyChord::new(key.code, key.modifiers)
+    }
+}
+
+// ~/.config/taman/keys.toml:
+//   preset = "vim"
+//   [keys]
+//   stop = "x"
+//   quit = ["q", "ctrl+c"]
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+struct KeyFile {
+    preset: Option<String>,
+    keys: BTreeMap<String, Keys>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyChord, InputAction)>, // the first binding of a chord wins
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::build(false, vec![]).0
+    }
+}
+
+impl Keymap {
+    // Returns the keymap plus anything wrong with keys.toml, conflicts included
+    pub fn load() -> (Self, Vec<String>) {
+        let path = crate::storage::data_dir().join("keys.toml");
+        match fs::read_to_string(&path) {
+            Ok(text) => Keymap::from_toml(&text),
+            Err(_) => (Keymap::default(), vec![]),
+        }
+    }
+
+    fn from_toml(text: &str) -> (Self, Vec<String>) {
+        let file: KeyFile = match toml::from_str(text) {
+            Ok(file) => file,
+            Err(err) => return (Keymap::default(), vec![format!("keys.toml could not be read: {}", err.message())]),
+        };
+        let mut problems = vec![];
+        let vim = match file.preset.as_deref() {
+            None | Some("default") => false,
+            Some("vim") => true,
+            Some(other) => {
+                problems.push(format!("keys.toml: unknown preset `{}`, expected `default` or `vim`", other));
+                false
+            }
+        };
+        let mut overrides = vec![];
+        for (name, keys) in file.keys {
+            let Some(action) = InputAction::from_name(&name) else {
+                problems.push(format!("keys.toml: unknown action `{}`", name));
+                continue;
+            };
+            let keys = match keys {
+                Keys::One(key) => vec![key],
+                Keys::Many(keys) => keys,
+            };
+            let mut chords = vec![];
+            for key in keys {
+                match KeyChord::parse(&key) {
+                    Ok(chord) => chords.push(chord),
+                    Err(err) => problems.push(format!("keys.toml: {}", err)),
+                }
+            }
+            overrides.push((action, chords));
+        }
+        let (keymap, conflicts) = Keymap::build(vim, overrides);
+        problems.extend(conflicts);
+        (keymap, problems)
+    }
+
+    // Keys set in the file replace that action's keys. They are bound first, so
+    // they win over a default that uses the same key.
+    fn build(vim: bool, overrides: Vec<(InputAction, Vec<KeyChord>)>) -> (Self, Vec<String>) {
+        let mut bindings: Vec<(KeyChord, InputAction)> = vec![];
+        for (action, chords) in &overrides {
+            bindings.extend(chords.iter().map(|chord| (*chord, *action)));
+        }
+        for (name, action) in ACTIONS {
+            if overrides.iter().any(|(overridden, _)| *overridden == action) {
+                continue;
+            }
+            let preset = VIM_KEYS.iter().filter(|_| vim).filter(|(n, _)| *n == name).flat_map(|(_, keys)| keys.iter());
+            let defaults = DEFAULT_KEYS.iter().filter(|(n, _)| *n == name).flat_map(|(_, keys)| keys.iter());
+            for key in preset.chain(defaults) {
+                if let Ok(chord) = KeyChord::parse(key) {
+                    bindings.push((chord, action));
+                }
+            }
+        }
+
+        let mut conflicts = vec![];
+        for (i, (chord, action)) in bindings.iter().enumerate() {
+            let first = bindings.iter().position(|(c, _)| c == chord).unwrap_or(i);
+            let winner = bindings[first].1;
+            if first < i && winner != *action {
+                conflicts.push(format!("`{}` is bound to both {} and {}, using {}", chord.spelling(), winner.name(), action.name(), winner.name()));
+            }
+        }
+        (Keymap { bindings }, conflicts)
+    }
+
+    pub fn action(&self, key: KeyEvent) -> Option<InputAction> {
+        let chord = KeyChord::from(key);
+        self.bindings.iter().find(|(c, _)| *c == chord).map(|(_, action)| *action)
+    }
+
+    // Chords that actually reach the action, in the order they were bound
+    pub fn keys(&self, action: InputAction) -> Vec<KeyChord> {
+        self.bindings.iter()
+            .enumerate()
+            .filter(|(i, (chord, a))| *a == action && self.bindings.iter().position(|(c, _)| c == chord) == Some(*i))
+            .map(|(_, (chord, _))| *chord)
+            .collect()
+    }
+
+    // "Start/Pause [Space] | Switch Blocks [←/→]": the first key of every action in a hint
+    pub fn footer(&self, hints: &[(&str, &[InputAction])]) -> String {
+        hints.iter()
+            .filter_map(|(label, actions)| {
+                let keys: Vec<String> = actions.iter().filter_map(|a| self.keys(*a).first().map(|k| k.label())).collect();
+                (!keys.is_empty()).then(|| format!("{} [{}]", label, keys.join("/")))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn parses_key_chords() {
+        let chord = |text: &str| KeyChord::parse(text).map(|c| (c.code, c.modifiers));
+        assert_eq!(chord("q"), Ok((KeyCode::Char('q'), KeyModifiers::NONE)));
+        assert_eq!(chord("shift+s"), Ok((KeyCode::Char('S'), KeyModifiers::NONE)));
+        assert_eq!(chord("ctrl+p"), Ok((KeyCode::Char('p'), KeyModifiers::CONTROL)));
+        assert_eq!(chord("ctrl++"), Ok((KeyCode::Char('+'), KeyModifiers::CONTROL)));
+        assert_eq!(chord("alt+left"), Ok((KeyCode::Left, KeyModifiers::ALT)));
+        assert_eq!(chord("shift+tab"), Ok((KeyCode::BackTab, KeyModifiers::NONE)));
+        assert_eq!(chord("space"), Ok((KeyCode::Char(' '), KeyModifiers::NONE)));
+        assert_eq!(chord("F12"), Ok((KeyCode::F(12), KeyModifiers::NONE)));
+        assert!(chord("f25").is_err());
+        assert!(chord("hyper+x").is_err());
+        assert!(chord("spacebar").is_err());
+    }
+
+    #[test]
+    fn reports_conflicts_and_lets_the_file_win() {
+        let (keymap, problems) = Keymap::from_toml("[keys]\nstop = \"q\"\n");
+        assert_eq!(keymap.action(press(KeyCode::Char('q'))), Some(InputAction::Stop));
+        assert_eq!(keymap.action(press(KeyCode::Char('Q'))), Some(InputAction::Quit));
+        assert_eq!(keymap.action(press(KeyCode::Char('s'))), None); // replaced, not added to
+        assert_eq!(problems, vec!["`q` is bound to both stop and quit, using stop".to_string()]);
+
+        let (_, problems) = Keymap::from_toml("preset = \"emacs\"\n[keys]\nsleep = \"z\"\n");
+        assert_eq!(problems.len(), 2);
+    }
+
+    #[test]
+    fn the_vim_preset_adds_hjkl_next_to_the_arrows() {
+        assert_eq!(Keymap::default().action(press(KeyCode::Char('j'))), None);
+        let (keymap, problems) = Keymap::from_toml("preset = \"vim\"\n");
+        assert!(problems.is_empty());
+        assert_eq!(keymap.action(press(KeyCode::Char('h'))), Some(InputAction::Left));
+        assert_eq!(keymap.action(press(KeyCode::Char('j'))), Some(InputAction::Down));
+        assert_eq!(keymap.action(press(KeyCode::Down)), Some(InputAction::Down));
+        assert_eq!(keymap.keys(InputAction::Up)[0].label(), "K");
+    }
+}
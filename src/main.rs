@@ -1,8 +1,19 @@
 mod app;
+mod cli;
+mod daemon;
+mod events;
 mod garden;
+mod goals;
+mod history;
+mod hooks;
 mod input;
+mod palette;
+mod keymap;
+mod notify;
 mod plant;
+mod species;
 mod storage;
+mod tasks;
 mod theme;
 mod timer;
 mod ui;
@@ -11,18 +22,33 @@ use std::io;
 use std::time::{Duration, Instant};
 
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use clap::Parser;
 use ratatui::{backend::CrosstermBackend, Terminal};
 use ratatui::text::Line;
 
 use crate::app::{App, Tab};
-use crate::input::handle_key;
-use crate::ui::{plant_ui, stats_ui, settings_ui, timer_ui};
+use crate::input::InputAction;
+use crate::keymap::Keymap;
+use crate::timer::TimerState;
+use crate::ui::{garden_ui, overlay_ui, plant_ui, stats_ui, settings_ui, timer_ui};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = cli::Cli::parse();
+    if let Some(command) = cli.command {
+        if let Err(err) = cli::run(command) {
+            eprintln!("taman: {}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    run_tui()
+}
+
+fn run_tui() -> Result<(), Box<dyn std::error::Error>> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -32,6 +58,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Create app
     let mut app = App::new();
+    if app.daemon.is_some() && app.status_message.is_none() {
+        app.status_message = Some("Attached to the taman daemon".to_string());
+    }
+    if app.daemon.is_none() {
+        app.data_lock = crate::storage::lock_data().ok().flatten();
+    }
+    let (keymap, key_problems) = Keymap::load();
+    if !key_problems.is_empty() {
+        // Added after whatever loading had to say, such as a restored backup
+        let notices: Vec<String> = app.status_message.take().into_iter().chain(key_problems).collect();
+        app.status_message = Some(notices.join("; "));
+    }
     let mut last_tick = Instant::now();
 
     loop {
@@ -48,17 +86,28 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .split(size);
 
             // Header: Tabs
-            let tabs = ratatui::widgets::Tabs::new(vec![
+            let titles = vec![
                 Line::from("⏳ Timer [1]").style(ratatui::style::Style::default().fg(app.theme.blocks)),
                 Line::from("🌱 Plant [2]").style(ratatui::style::Style::default().fg(app.theme.blocks)),
                 Line::from("📊 Stats [3]").style(ratatui::style::Style::default().fg(app.theme.blocks)),
                 Line::from("⚙️ Settings [4]").style(ratatui::style::Style::default().fg(app.theme.blocks)),
-            ])
+                Line::from("🏡 Garden [5]").style(ratatui::style::Style::default().fg(app.theme.blocks)),
+            ];
+            // Tabs pads every title with a space on each side and puts a divider between them
+            let mut x = chunks[0].x;
+            app.hit_areas.tabs = titles.iter().enumerate().map(|(i, title)| {
+                let width = title.width() as u16 + 2;
+                let rect = ratatui::layout::Rect { x, y: chunks[0].y, width, height: chunks[0].height }.intersection(chunks[0]);
+                x += width + 1;
+                (rect, i as u8 + 1)
+            }).collect();
+            let tabs = ratatui::widgets::Tabs::new(titles)
             .select(match app.tab {
                 Tab::Timer => 0,
                 Tab::Plant => 1,
                 Tab::Stats => 2,
                 Tab::Settings => 3,
+                Tab::Garden => 4,
             })
             .style(ratatui::style::Style::default().fg(app.theme.tabs))
             .highlight_style(ratatui::style::Style::default().fg(app.theme.highlight).add_modifier(ratatui::style::Modifier::BOLD));
@@ -68,34 +117,112 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             match app.tab {
                 Tab::Timer => timer_ui::draw_timer(f, &mut app, chunks[1]),
                 Tab::Plant => plant_ui::draw_plant(f, &app, chunks[1]),
-                Tab::Stats => stats_ui::draw_stats(f, &app, chunks[1]),
-                Tab::Settings => settings_ui::draw_settings(f, &app, chunks[1]),
+                Tab::Stats => stats_ui::draw_stats(f, &mut app, chunks[1]),
+                Tab::Settings => settings_ui::draw_settings(f, &mut app, chunks[1]),
+                Tab::Garden => garden_ui::draw_garden(f, &mut app, chunks[1]),
+            }
+            if app.timer.state == TimerState::Suspended {
+                timer_ui::draw_suspend_prompt(f, &app, chunks[1]);
+            }
+            match &app.overlay {
+                Some(app::Overlay::Help) => overlay_ui::draw_help(f, &app, &keymap, chunks[1]),
+                Some(app::Overlay::Palette(palette)) => overlay_ui::draw_palette(f, &app, palette, chunks[1]),
+                Some(app::Overlay::Tasks(picker)) => overlay_ui::draw_tasks(f, &app, picker, chunks[1]),
+                None => {}
             }
 
             // Footer: Status and hints
-            let footer_text = match app.tab {
-                Tab::Timer => "Start/Pause [Space] | Stop [S] | Switch Blocks [←/→] | Select/Adjust [↑/↓] | Quit [Q]",
-                Tab::Plant => "Quit [Q]",
-                Tab::Stats => "Quit [Q]",
-                Tab::Settings => "Switch Blocks [←/→] | Select/Adjust [↑/↓] | Quit [Q]",
+            let footer_text = if app.timer.state == TimerState::Suspended {
+                keymap.footer(&[
+                    ("Count Gap", &[InputAction::CountGap]),
+                    ("Discard Gap", &[InputAction::DiscardGap]),
+                    ("End Session", &[InputAction::EndSession]),
+                    ("Help", &[InputAction::Help]),
+                    ("Quit", &[InputAction::Quit]),
+                ])
+            } else {
+                match app.tab {
+                    Tab::Timer => keymap.footer(&[
+                        ("Start/Pause", &[InputAction::Space]),
+                        ("Stop", &[InputAction::Stop]),
+                        ("Reset", &[InputAction::Reset]),
+                        ("Task", &[InputAction::Tasks]),
+                        ("Switch Blocks", &[InputAction::Left, InputAction::Right]),
+                        ("Select/Adjust", &[InputAction::Up, InputAction::Down]),
+                        ("Help", &[InputAction::Help]),
+                        ("Quit", &[InputAction::Quit]),
+                    ]),
+                    Tab::Plant => keymap.footer(&[("Help", &[InputAction::Help]), ("Quit", &[InputAction::Quit])]),
+                    Tab::Stats if app.in_heatmap() => keymap.footer(&[
+                        ("Move Cursor", &[InputAction::Left, InputAction::Up, InputAction::Down, InputAction::Right]),
+                        ("Back", &[InputAction::Enter]),
+                        ("Help", &[InputAction::Help]),
+                        ("Quit", &[InputAction::Quit]),
+                    ]),
+                    Tab::Stats if app.stats_selected == 9 => keymap.footer(&[
+                        ("Cursor", &[InputAction::Enter]),
+                        ("Select", &[InputAction::Up, InputAction::Down]),
+                        ("Help", &[InputAction::Help]),
+                        ("Quit", &[InputAction::Quit]),
+                    ]),
+                    Tab::Stats => keymap.footer(&[
+                        ("Period", &[InputAction::PrevPeriod, InputAction::NextPeriod]),
+                        ("Older/Newer", &[InputAction::Left, InputAction::Right]),
+                        ("Select", &[InputAction::Up, InputAction::Down]),
+                        ("Help", &[InputAction::Help]),
+                        ("Quit", &[InputAction::Quit]),
+                    ]),
+                    Tab::Settings => keymap.footer(&[
+                        ("Switch Blocks", &[InputAction::Left, InputAction::Right]),
+                        ("Select/Adjust", &[InputAction::Up, InputAction::Down]),
+                        ("Help", &[InputAction::Help]),
+                        ("Quit", &[InputAction::Quit]),
+                    ]),
+                    Tab::Garden => keymap.footer(&[
+                        ("Select Plant", &[InputAction::Left, InputAction::Up, InputAction::Down, InputAction::Right]),
+                        ("Filter Month", &[InputAction::PrevPeriod, InputAction::NextPeriod]),
+                        ("Help", &[InputAction::Help]),
+                        ("Quit", &[InputAction::Quit]),
+                    ]),
+                }
+            };
+            let footer = match &app.status_message {
+                Some(message) => ratatui::widgets::Paragraph::new(message.as_str())
+                    .style(ratatui::style::Style::default().fg(app.theme.highlight)),
+                None => ratatui::widgets::Paragraph::new(footer_text)
+                    .style(ratatui::style::Style::default().fg(app.theme.secondary_text)),
             };
-            let footer = ratatui::widgets::Paragraph::new(footer_text)
-                .style(ratatui::style::Style::default().fg(app.theme.secondary_text));
             f.render_widget(footer, chunks[2]);
         })?;
 
         // Handle events
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if let Some(action) = handle_key(key) {
-                    app.handle_input(action);
+            match event::read()? {
+                Event::Key(key) => {
+                    app.status_message = None;
+                    // An open overlay takes every key until it closes
+                    if app.overlay.is_some() {
+                        app.handle_overlay_key(key);
+                        app.save_if_changed();
+                    } else if let Some(action) = keymap.action(key) {
+                        app.handle_input(action);
+                        app.save_if_changed();
+                    }
+                }
+                // Movement is reported too, but only clicks and the wheel do anything
+                Event::Mouse(mouse) if matches!(mouse.kind, MouseEventKind::Down(_) | MouseEventKind::ScrollUp | MouseEventKind::ScrollDown) => {
+                    app.status_message = None;
+                    app.handle_mouse(mouse);
+                    app.save_if_changed();
                 }
+                _ => {}
             }
         }
 
         // Tick
         if last_tick.elapsed() >= Duration::from_millis(250) {
             app.tick();
+            app.dispatch_events();
             last_tick = Instant::now();
         }
 
@@ -117,5 +244,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     terminal.show_cursor()?;
 
+    // Hooks of the last few events may still be running
+    app.finish_events();
+    if let Some(err) = app.status_message.take() {
+        eprintln!("taman: {}", err);
+    }
     Ok(())
 }
\ No newline at end of file
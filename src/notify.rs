@@ -0,0 +1,336 @@
+use std::io::{self, IsTerminal, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::AppEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifierKind {
+    Bell,
+    Osc9,
+    Osc777,
+    Dbus,
+}
+
+impl NotifierKind {
+    fn name(&self) -> &'static str {
+        match self {
+            NotifierKind::Bell => "Bell",
+            NotifierKind::Osc9 => "OSC 9",
+            NotifierKind::Osc777 => "OSC 777",
+            NotifierKind::Dbus => "D-Bus",
+        }
+    }
+}
+
+// Which events a notifier fires for
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct EventToggles {
+    pub session_start: bool,
+    pub session_complete: bool,
+    pub session_abandoned: bool,
+    pub plant_complete: bool,
+    pub streak_broken: bool,
+}
+
+impl EventToggles {
+    fn completions() -> Self {
+        EventToggles { session_complete: true, plant_complete: true, ..EventToggles::default() }
+    }
+
+    pub fn enabled(&self, event: &AppEvent) -> bool {
+        match event {
+            AppEvent::SessionStart { .. } => self.session_start,
+            AppEvent::SessionComplete { .. } => self.session_complete,
+            AppEvent::SessionAbandoned { .. } => self.session_abandoned,
+            AppEvent::PlantComplete { .. } => self.plant_complete,
+            AppEvent::StreakBroken { .. } => self.streak_broken,
+        }
+    }
+}
+
+// Only the bell is on out of the box; the others depend on the terminal or desktop
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub bell: EventToggles,
+    pub osc9: EventToggles,   // iTerm2, WezTerm, Windows Terminal, ...
+    pub osc777: EventToggles, // urxvt, foot, Ghostty, ...
+    pub dbus: EventToggles,   // freedesktop notification daemon
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        NotificationSettings {
+            bell: EventToggles::completions(),
+            osc9: EventToggles::default(),
+            osc777: EventToggles::default(),
+            dbus: EventToggles::default(),
+        }
+    }
+}
+
+impl NotificationSettings {
+    fn toggles(&self, kind: NotifierKind) -> &EventToggles {
+        match kind {
+            NotifierKind::Bell => &self.bell,
+            NotifierKind::Osc9 => &self.osc9,
+            NotifierKind::Osc777 => &self.osc777,
+            NotifierKind::Dbus => &self.dbus,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub event: &'static str,
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn of(event: &AppEvent) -> Self {
+        let (title, body) = match event {
+            AppEvent::SessionStart { session_type, duration_minutes, .. } => {
+                (format!("{} started", session_type.name()), format!("{} minutes on the clock", duration_minutes))
+            }
+            AppEvent::SessionComplete { session_type, duration_minutes, .. } => {
+                (format!("{} complete", session_type.name()), format!("{} minute session finished", duration_minutes))
+            }
+            AppEvent::SessionAbandoned { session_type, duration_minutes, elapsed_minutes, .. } => {
+                (format!("{} stopped", session_type.name()), format!("Stopped after {} of {} minutes", elapsed_minutes, duration_minutes))
+            }
+            AppEvent::PlantComplete { species_name, sessions, .. } => {
+                (format!("{} fully grown", species_name), format!("Moved to the garden after {} sessions", sessions))
+            }
+            AppEvent::StreakBroken { streak_days, .. } => {
+                ("Streak broken".to_string(), format!("Your {} day streak has ended", streak_days))
+            }
+        };
+        Notification { event: event.name(), title, body }
+    }
+}
+
+pub trait Notifier: std::fmt::Debug {
+    fn kind(&self) -> NotifierKind;
+    fn send(&mut self, notification: &Notification) -> io::Result<()>;
+}
+
+// Sends every event to the notifiers that have it switched on
+#[derive(Debug)]
+pub struct Notifiers {
+    list: Vec<Box<dyn Notifier>>,
+    pub terminal: bool, // false without a terminal to write to, i.e. in the daemon
+    pub desktop: bool,  // false in a client of the daemon, which notifies the desktop itself
+}
+
+impl Notifiers {
+    pub fn new(list: Vec<Box<dyn Notifier>>) -> Self {
+        Notifiers { list, terminal: true, desktop: true }
+    }
+
+    pub fn standard() -> Self {
+        Notifiers::new(vec![
+            Box::new(TerminalNotifier { kind: NotifierKind::Bell }),
+            Box::new(TerminalNotifier { kind: NotifierKind::Osc9 }),
+            Box::new(TerminalNotifier { kind: NotifierKind::Osc777 }),
+            Box::new(DbusNotifier::default()),
+        ])
+    }
+
+    // Returns what went wrong, for the footer
+    pub fn notify(&mut self, settings: &NotificationSettings, event: &AppEvent) -> Vec<String> {
+        let notification = Notification::of(event);
+        let mut errors = vec![];
+        for notifier in &mut self.list {
+            let kind = notifier.kind();
+            let allowed = match kind {
+                NotifierKind::Dbus => self.desktop,
+                _ => self.terminal,
+            };
+            if !allowed || !settings.toggles(kind).enabled(event) {
+                continue;
+            }
+            if let Err(err) = notifier.send(&notification) {
+                errors.push(format!("{} notification failed: {}", kind.name(), err));
+            }
+        }
+        errors
+    }
+}
+
+// Escape sequences written straight to the terminal; they don't move the cursor, so the TUI is left alone
+#[derive(Debug)]
+struct TerminalNotifier {
+    kind: NotifierKind,
+}
+
+impl Notifier for TerminalNotifier {
+    fn kind(&self) -> NotifierKind {
+        self.kind
+    }
+
+    fn send(&mut self, notification: &Notification) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        // Redirected output such as `taman export > out.csv` must not pick up escape sequences
+        if !stdout.is_terminal() {
+            return Ok(());
+        }
+        let sequence = escape_sequence(self.kind, notification, std::env::var_os("TMUX").is_some());
+        stdout.write_all(sequence.as_bytes())?;
+        stdout.flush()
+    }
+}
+
+fn escape_sequence(kind: NotifierKind, notification: &Notification, in_tmux: bool) -> String {
+    let sequence = match kind {
+        NotifierKind::Bell => return "\x07".to_string(), // tmux passes the bell on by itself
+        NotifierKind::Osc9 => format!("\x1b]9;{}: {}\x07", clean(&notification.title), clean(&notification.body)),
+        NotifierKind::Osc777 => format!("\x1b]777;notify;{};{}\x07", clean(&notification.title).replace(';', ","), clean(&notification.body)),
+        NotifierKind::Dbus => return String::new(),
+    };
+    // tmux only forwards sequences wrapped in a passthrough, with every ESC doubled
+    // (and `set -g allow-passthrough on`)
+    match in_tmux {
+        true => format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b")),
+        false => sequence,
+    }
+}
+
+// A stray control character would end the sequence early
+fn clean(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+#[derive(Debug, Default)]
+struct DbusNotifier {
+    #[cfg(target_os = "linux")]
+    connection: Option<zbus::blocking::Connection>,
+}
+
+impl Notifier for DbusNotifier {
+    fn kind(&self) -> NotifierKind {
+        NotifierKind::Dbus
+    }
+
+    #[cfg(target_os = "linux")]
+    fn send(&mut self, notification: &Notification) -> io::Result<()> {
+        let connection = match &self.connection {
+            Some(connection) => connection,
+            None => self.connection.insert(zbus::blocking::Connection::session().map_err(io::Error::other)?),
+        };
+        let hints = std::collections::HashMap::<&str, zbus::zvariant::Value>::new();
+        connection.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &("taman", 0u32, "", notification.title.as_str(), notification.body.as_str(), Vec::<&str>::new(), hints, -1i32),
+        ).map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn send(&mut self, _notification: &Notification) -> io::Result<()> {
+        Err(io::Error::other("D-Bus notifications are only available on Linux"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::SessionType;
+    use chrono::Local;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Sent = Rc<RefCell<Vec<(NotifierKind, Notification)>>>;
+
+    #[derive(Debug)]
+    struct RecordingNotifier {
+        kind: NotifierKind,
+        sent: Sent,
+    }
+
+    impl Notifier for RecordingNotifier {
+        fn kind(&self) -> NotifierKind {
+            self.kind
+        }
+
+        fn send(&mut self, notification: &Notification) -> io::Result<()> {
+            self.sent.borrow_mut().push((self.kind, notification.clone()));
+            Ok(())
+        }
+    }
+
+    fn recording(kinds: &[NotifierKind]) -> (Notifiers, Sent) {
+        let sent = Rc::new(RefCell::new(vec![]));
+        let list = kinds.iter().map(|&kind| Box::new(RecordingNotifier { kind, sent: sent.clone() }) as Box<dyn Notifier>).collect();
+        (Notifiers::new(list), sent)
+    }
+
+    fn completed_focus() -> AppEvent {
+        AppEvent::SessionComplete { session_type: SessionType::Focus, duration_minutes: 25, end_time: Local::now() }
+    }
+
+    #[test]
+    fn only_notifiers_with_the_event_switched_on_are_used() {
+        let (mut notifiers, sent) = recording(&[NotifierKind::Bell, NotifierKind::Osc777, NotifierKind::Dbus]);
+        let mut settings = NotificationSettings::default();
+        settings.dbus.session_complete = true;
+        settings.osc777.session_start = true;
+
+        assert!(notifiers.notify(&settings, &completed_focus()).is_empty());
+        let sent = sent.borrow();
+        let kinds: Vec<NotifierKind> = sent.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, vec![NotifierKind::Bell, NotifierKind::Dbus]);
+        assert_eq!(sent[0].1, Notification {
+            event: "session_complete",
+            title: "Focus complete".to_string(),
+            body: "25 minute session finished".to_string(),
+        });
+    }
+
+    #[test]
+    fn the_default_settings_ring_the_bell_for_completions_only() {
+        let (mut notifiers, sent) = recording(&[NotifierKind::Bell, NotifierKind::Osc9]);
+        let settings = NotificationSettings::default();
+        notifiers.notify(&settings, &AppEvent::SessionStart { session_type: SessionType::Focus, duration_minutes: 25, started_at: Local::now() });
+        notifiers.notify(&settings, &AppEvent::StreakBroken { streak_days: 4, last_active_day: None });
+        notifiers.notify(&settings, &AppEvent::PlantComplete {
+            species: "fern".to_string(),
+            species_name: "Fern".to_string(),
+            sessions: 8,
+            completed_at: Local::now(),
+        });
+        let sent = sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, NotifierKind::Bell);
+        assert_eq!(sent[0].1.title, "Fern fully grown");
+    }
+
+    #[test]
+    fn daemon_and_clients_leave_each_other_their_notifiers() {
+        let settings = NotificationSettings { dbus: EventToggles::completions(), ..NotificationSettings::default() };
+
+        let (mut daemon, sent) = recording(&[NotifierKind::Bell, NotifierKind::Dbus]);
+        daemon.terminal = false;
+        daemon.notify(&settings, &completed_focus());
+        assert_eq!(sent.borrow().iter().map(|(kind, _)| *kind).collect::<Vec<_>>(), vec![NotifierKind::Dbus]);
+
+        let (mut client, sent) = recording(&[NotifierKind::Bell, NotifierKind::Dbus]);
+        client.desktop = false;
+        client.notify(&settings, &completed_focus());
+        assert_eq!(sent.borrow().iter().map(|(kind, _)| *kind).collect::<Vec<_>>(), vec![NotifierKind::Bell]);
+    }
+
+    #[test]
+    fn osc_sequences_are_wrapped_for_tmux() {
+        let notification = Notification { event: "session_complete", title: "Focus; done".to_string(), body: "25\nmin".to_string() };
+        assert_eq!(escape_sequence(NotifierKind::Osc9, &notification, false), "\x1b]9;Focus; done: 25min\x07");
+        assert_eq!(escape_sequence(NotifierKind::Osc777, &notification, false), "\x1b]777;notify;Focus, done;25min\x07");
+        assert_eq!(escape_sequence(NotifierKind::Osc777, &notification, true), "\x1bPtmux;\x1b\x1b]777;notify;Focus, done;25min\x07\x1b\\");
+        assert_eq!(escape_sequence(NotifierKind::Bell, &notification, true), "\x07");
+    }
+}
@@ -0,0 +1,110 @@
+use crate::cli::ExportFormat;
+use crate::input::InputAction;
+use crate::theme::Themes;
+use crate::timer::SessionType;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Palette {
+    pub query: String,
+    pub selected: usize, // index into the matches
+}
+
+// A palette entry runs its actions through App::handle_input, one after the other
+#[derive(Debug, Clone)]
+pub struct PaletteCommand {
+    pub label: String,
+    pub actions: Vec<InputAction>,
+}
+
+fn command(label: impl Into<String>, actions: &[InputAction]) -> PaletteCommand {
+    PaletteCommand { label: label.into(), actions: actions.to_vec() }
+}
+
+pub fn commands(themes: &Themes) -> Vec<PaletteCommand> {
+    let mut commands = vec![
+        command("Start focus", &[InputAction::StartSession(SessionType::Focus)]),
+        command("Start short break", &[InputAction::StartSession(SessionType::ShortBreak)]),
+        command("Start long break", &[InputAction::StartSession(SessionType::LongBreak)]),
+        command("Pause or resume timer", &[InputAction::TogglePause]),
+        // Stop and reset only work on the Timer tab
+        command("Stop session", &[InputAction::Tab(1), InputAction::Stop]),
+        command("Reset session", &[InputAction::Tab(1), InputAction::Reset]),
+        command("Clear auto-run queue", &[InputAction::ClearQueue]),
+        command("Pick task", &[InputAction::Tasks]),
+        command("Go to timer", &[InputAction::Tab(1)]),
+        command("Go to plant", &[InputAction::Tab(2)]),
+        command("Go to stats", &[InputAction::Tab(3)]),
+        command("Go to settings", &[InputAction::Tab(4)]),
+        command("Go to garden", &[InputAction::Tab(5)]),
+    ];
+    for (i, theme) in themes.list.iter().enumerate() {
+        commands.push(command(format!("Switch theme to {}", theme.name), &[InputAction::SetTheme(i)]));
+    }
+    commands.extend([
+        command("Export stats as JSON", &[InputAction::Export(ExportFormat::Json)]),
+        command("Export stats as CSV", &[InputAction::Export(ExportFormat::Csv)]),
+        command("Show help", &[InputAction::Help]),
+        command("Quit", &[InputAction::Quit]),
+    ]);
+    commands
+}
+
+impl Palette {
+    // Indices of the commands matching the query, best first
+    pub fn matches(&self, commands: &[PaletteCommand]) -> Vec<usize> {
+        let mut scored: Vec<(i64, usize)> = commands.iter()
+            .enumerate()
+            .filter_map(|(i, c)| fuzzy_score(&self.query, &c.label).map(|score| (score, i)))
+            .collect();
+        // Stable, so equal scores keep the list order
+        scored.sort_by_key(|(score, _)| -score);
+        scored.into_iter().map(|(_, i)| i).collect()
+    }
+}
+
+// The query's letters have to appear in order. Runs of letters and letters
+// at the start of a word score higher, so "lb" finds "Start long break".
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (position..text.len()).find(|&i| text[i] == wanted)?;
+        score += 1;
+        if found == 0 || text[found - 1] == ' ' {
+            score += 5;
+        }
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 3;
+        }
+        score -= (found - position).min(5) as i64 / 2;
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn best_match(query: &str) -> Option<String> {
+        let commands = commands(&Themes::default());
+        let palette = Palette { query: query.to_string(), selected: 0 };
+        palette.matches(&commands).first().map(|&i| commands[i].label.clone())
+    }
+
+    #[test]
+    fn word_starts_win() {
+        assert_eq!(best_match("lb").as_deref(), Some("Start long break"));
+        assert_eq!(best_match("sf").as_deref(), Some("Start focus"));
+        assert_eq!(best_match("").as_deref(), Some("Start focus")); // everything matches, in list order
+    }
+
+    #[test]
+    fn letters_out_of_order_or_missing_match_nothing() {
+        assert_eq!(fuzzy_score("bl", "Start long break"), None);
+        assert_eq!(best_match("zzz"), None);
+    }
+}
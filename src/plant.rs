@@ -1,5 +1,53 @@
 
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum GrowthMode {
+    PerSession,
+    PerMinute,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct GrowthRules {
+    pub mode: GrowthMode,
+    pub points: u32, // per session or per minute, depending on mode
+    pub min_minutes: u64, // shorter sessions don't count
+    pub focus: bool,
+    pub short_breaks: bool,
+    pub long_breaks: bool,
+}
+
+impl Default for GrowthRules {
+    fn default() -> Self {
+        GrowthRules {
+            mode: GrowthMode::PerSession,
+            points: 1,
+            min_minutes: 0,
+            focus: true,
+            short_breaks: false,
+            long_breaks: false,
+        }
+    }
+}
+
+impl GrowthRules {
+    pub fn points_for(&self, session_type: crate::timer::SessionType, minutes: u64) -> u32 {
+        let counts = match session_type {
+            crate::timer::SessionType::Focus => self.focus,
+            crate::timer::SessionType::ShortBreak => self.short_breaks,
+            crate::timer::SessionType::LongBreak => self.long_breaks,
+            crate::timer::SessionType::Unknown => false,
+        };
+        if !counts || minutes < self.min_minutes {
+            return 0;
+        }
+        match self.mode {
+            GrowthMode::PerSession => self.points,
+            GrowthMode::PerMinute => self.points.saturating_mul(minutes as u32),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum PlantStage {
     Seed,
@@ -45,69 +93,126 @@ impl PlantStage {
 
 
 
-    pub fn icon(&self) -> &'static str {
-        match self {
-            PlantStage::Seed => "🌰",
-            PlantStage::Sprout => "🍃",
-            PlantStage::Seedling => "🌱",
-            PlantStage::YoungPlant => "🌿",
-            PlantStage::FullGrownPlant => "🪴",
-        }
+    pub fn icon(&self, species: &crate::species::Species) -> &'static str {
+        species.stages[self.to_u32() as usize].icon
     }
 
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Plant {
+    #[serde(default = "default_species")]
+    pub species: String,
     pub stage: PlantStage,
     pub growth_points: u32,
+    #[serde(default)]
+    pub planted_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub sessions: u32, // sessions that added growth
+    #[serde(default)]
+    pub strikes: u32, // abandoned focus sessions in strict mode
+}
+
+fn default_species() -> String {
+    crate::species::DEFAULT_SPECIES.to_string()
 }
 
 impl Plant {
-    pub fn new() -> Self {
+    pub fn new(species: &crate::species::Species) -> Self {
         Plant {
+            species: species.id.to_string(),
             stage: PlantStage::Seed,
             growth_points: 0,
+            planted_at: Some(chrono::Utc::now()),
+            sessions: 0,
+            strikes: 0,
         }
     }
 
-    pub fn from_stage(stage: u32, points: u32) -> Self {
-        Plant {
+    pub fn from_stage(species: &str, stage: u32, points: u32) -> Self {
+        let mut plant = Plant {
+            species: crate::species::find(species).id.to_string(),
             stage: PlantStage::from_u32(stage),
             growth_points: points,
-        }
+            planted_at: None,
+            sessions: 0,
+            strikes: 0,
+        };
+        plant.update_stage();
+        plant
     }
 
-    pub fn add_growth(&mut self) {
-        if self.growth_points < 10 {
-            self.growth_points += 1;
+    pub fn species(&self) -> &'static crate::species::Species {
+        crate::species::find(&self.species)
+    }
+
+    pub fn stage_art(&self) -> &'static crate::species::StageArt {
+        &self.species().stages[self.stage.to_u32() as usize]
+    }
+
+    pub fn add_growth(&mut self, points: u32) {
+        let max = self.species().max_points();
+        if self.growth_points < max {
+            self.growth_points = (self.growth_points + points).min(max);
+            self.sessions += 1;
             self.update_stage();
         }
     }
 
     pub fn update_stage(&mut self) {
-        self.stage = match self.growth_points {
-            0..=1 => PlantStage::Seed,
-            2..=4 => PlantStage::Sprout,
-            5..=7 => PlantStage::Seedling,
-            8..=9 => PlantStage::YoungPlant,
-            10.. => PlantStage::FullGrownPlant,
-        };
+        let stages = &self.species().stages;
+        let index = stages.iter().rposition(|s| self.growth_points >= s.threshold).unwrap_or(0);
+        self.stage = PlantStage::from_u32(index as u32);
+    }
+
+    // Returns true once the plant has been hurt too often to survive
+    pub fn wither(&mut self, penalty: u32, max_strikes: u32) -> bool {
+        self.growth_points = self.growth_points.saturating_sub(penalty);
+        self.strikes += 1;
+        self.update_stage();
+        self.strikes >= max_strikes.max(1)
     }
 
     pub fn is_complete(&self) -> bool {
-        self.growth_points >= 10
+        self.growth_points >= self.species().max_points()
     }
 
+    pub fn next_stage_art(&self) -> Option<&'static crate::species::StageArt> {
+        self.species().stages.get(self.stage.to_u32() as usize + 1)
+    }
 
+    pub fn points_to_next_stage(&self) -> u32 {
+        self.next_stage_art().map(|s| s.threshold.saturating_sub(self.growth_points)).unwrap_or(0)
+    }
 
-    pub fn sessions_to_next_stage(&self) -> u32 {
-        match self.growth_points {
-            0..=1 => 2 - self.growth_points,
-            2..=4 => 5 - self.growth_points,
-            5..=7 => 8 - self.growth_points,
-            8..=9 => 10 - self.growth_points,
-            _ => 0,
+    // Fraction of the way from the current stage to the next one
+    pub fn stage_progress(&self) -> f64 {
+        match self.next_stage_art() {
+            Some(next) => {
+                let start = self.stage_art().threshold;
+                (self.growth_points - start) as f64 / (next.threshold - start) as f64
+            }
+            None => 1.0,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::SessionType;
+
+    #[test]
+    fn growth_follows_the_rules() {
+        let per_session = GrowthRules { points: 2, min_minutes: 10, ..GrowthRules::default() };
+        assert_eq!(per_session.points_for(SessionType::Focus, 25), 2);
+        assert_eq!(per_session.points_for(SessionType::Focus, 10), 2);
+        assert_eq!(per_session.points_for(SessionType::Focus, 9), 0); // under the minimum
+        assert_eq!(per_session.points_for(SessionType::ShortBreak, 25), 0);
+
+        let per_minute = GrowthRules { mode: GrowthMode::PerMinute, long_breaks: true, ..GrowthRules::default() };
+        assert_eq!(per_minute.points_for(SessionType::Focus, 25), 25);
+        assert_eq!(per_minute.points_for(SessionType::LongBreak, 15), 15);
+        assert_eq!(per_minute.points_for(SessionType::ShortBreak, 5), 0);
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+impl Rarity {
+    // Relative odds of a random seed being of this rarity
+    pub fn weight(&self) -> u64 {
+        match self {
+            Rarity::Common => 60,
+            Rarity::Uncommon => 25,
+            Rarity::Rare => 12,
+            Rarity::Legendary => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for Rarity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Rarity::Common => write!(f, "Common"),
+            Rarity::Uncommon => write!(f, "Uncommon"),
+            Rarity::Rare => write!(f, "Rare"),
+            Rarity::Legendary => write!(f, "Legendary"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StageArt {
+    pub name: &'static str,
+    pub icon: &'static str,
+    pub threshold: u32, // growth points needed to reach this stage
+}
+
+// Every species grows through the five steps of PlantStage, with its own art and pace
+#[derive(Debug)]
+pub struct Species {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub rarity: Rarity,
+    pub stages: [StageArt; 5],
+}
+
+impl Species {
+    pub fn max_points(&self) -> u32 {
+        self.stages[4].threshold
+    }
+
+    pub fn icon(&self) -> &'static str {
+        self.stages[4].icon
+    }
+}
+
+pub const DEFAULT_SPECIES: &str = "potted_plant";
+
+pub const SPECIES: &[Species] = &[
+    Species {
+        id: "potted_plant",
+        name: "Potted Plant",
+        rarity: Rarity::Common,
+        stages: [
+            StageArt { name: "Seed", icon: "🌰", threshold: 0 },
+            StageArt { name: "Sprout", icon: "🍃", threshold: 2 },
+            StageArt { name: "Seedling", icon: "🌱", threshold: 5 },
+            StageArt { name: "Young Plant", icon: "🌿", threshold: 8 },
+            StageArt { name: "Fully Grown Plant", icon: "🪴", threshold: 10 },
+        ],
+    },
+    Species {
+        id: "sunflower",
+        name: "Sunflower",
+        rarity: Rarity::Common,
+        stages: [
+            StageArt { name: "Seed", icon: "🌰", threshold: 0 },
+            StageArt { name: "Sprout", icon: "🌱", threshold: 2 },
+            StageArt { name: "Stalk", icon: "🌿", threshold: 4 },
+            StageArt { name: "Bud", icon: "🌼", threshold: 7 },
+            StageArt { name: "Sunflower", icon: "🌻", threshold: 10 },
+        ],
+    },
+    Species {
+        id: "tulip",
+        name: "Tulip",
+        rarity: Rarity::Uncommon,
+        stages: [
+            StageArt { name: "Bulb", icon: "🧅", threshold: 0 },
+            StageArt { name: "Shoot", icon: "🌱", threshold: 3 },
+            StageArt { name: "Leaves", icon: "🍃", threshold: 5 },
+            StageArt { name: "Bud", icon: "🌿", threshold: 8 },
+            StageArt { name: "Tulip", icon: "🌷", threshold: 11 },
+        ],
+    },
+    Species {
+        id: "cactus",
+        name: "Cactus",
+        rarity: Rarity::Uncommon,
+        stages: [
+            StageArt { name: "Seed", icon: "🌰", threshold: 0 },
+            StageArt { name: "Nub", icon: "🌱", threshold: 3 },
+            StageArt { name: "Pad", icon: "🍃", threshold: 6 },
+            StageArt { name: "Young Cactus", icon: "🌿", threshold: 9 },
+            StageArt { name: "Cactus", icon: "🌵", threshold: 12 },
+        ],
+    },
+    Species {
+        id: "mushroom",
+        name: "Mushroom",
+        rarity: Rarity::Uncommon,
+        stages: [
+            StageArt { name: "Spore", icon: "🟤", threshold: 0 },
+            StageArt { name: "Mycelium", icon: "🍂", threshold: 1 },
+            StageArt { name: "Pinhead", icon: "🌰", threshold: 3 },
+            StageArt { name: "Button", icon: "🟫", threshold: 5 },
+            StageArt { name: "Mushroom", icon: "🍄", threshold: 8 },
+        ],
+    },
+    Species {
+        id: "evergreen",
+        name: "Evergreen",
+        rarity: Rarity::Rare,
+        stages: [
+            StageArt { name: "Pine Cone", icon: "🌰", threshold: 0 },
+            StageArt { name: "Sprout", icon: "🌱", threshold: 3 },
+            StageArt { name: "Sapling", icon: "🌿", threshold: 7 },
+            StageArt { name: "Young Tree", icon: "🎄", threshold: 11 },
+            StageArt { name: "Evergreen", icon: "🌲", threshold: 15 },
+        ],
+    },
+    Species {
+        id: "cherry_blossom",
+        name: "Cherry Blossom",
+        rarity: Rarity::Rare,
+        stages: [
+            StageArt { name: "Pit", icon: "🍒", threshold: 0 },
+            StageArt { name: "Sprout", icon: "🌱", threshold: 3 },
+            StageArt { name: "Sapling", icon: "🌿", threshold: 7 },
+            StageArt { name: "Tree", icon: "🌳", threshold: 11 },
+            StageArt { name: "Cherry Blossom", icon: "🌸", threshold: 15 },
+        ],
+    },
+    Species {
+        id: "lotus",
+        name: "Lotus",
+        rarity: Rarity::Legendary,
+        stages: [
+            StageArt { name: "Seed Pod", icon: "🌰", threshold: 0 },
+            StageArt { name: "Root", icon: "🌱", threshold: 4 },
+            StageArt { name: "Lily Pad", icon: "🍃", threshold: 9 },
+            StageArt { name: "Bud", icon: "🌿", threshold: 14 },
+            StageArt { name: "Lotus", icon: "🪷", threshold: 20 },
+        ],
+    },
+];
+
+pub fn find(id: &str) -> &'static Species {
+    SPECIES.iter().find(|s| s.id == id).unwrap_or(&SPECIES[0])
+}
+
+// Weighted by rarity, so legendary seeds stay rare
+pub fn random() -> &'static Species {
+    let total: u64 = SPECIES.iter().map(|s| s.rarity.weight()).sum();
+    weighted(RandomState::new().build_hasher().finish() % total)
+}
+
+// Every species covers as many rolls as its rarity weighs, in list order
+fn weighted(mut roll: u64) -> &'static Species {
+    for species in SPECIES {
+        if roll < species.rarity.weight() {
+            return species;
+        }
+        roll -= species.rarity.weight();
+    }
+    &SPECIES[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plant::{Plant, PlantStage};
+
+    #[test]
+    fn stages_follow_each_species_thresholds() {
+        let mut tulip = Plant::new(find("tulip"));
+        tulip.add_growth(2);
+        assert_eq!(tulip.stage, PlantStage::Seed);
+        tulip.add_growth(1);
+        assert_eq!(tulip.stage_art().name, "Shoot");
+        tulip.add_growth(7);
+        assert_eq!(tulip.stage, PlantStage::YoungPlant);
+        assert_eq!(tulip.points_to_next_stage(), 1);
+        tulip.add_growth(5);
+        assert_eq!(tulip.growth_points, 11); // capped at the last stage
+        assert!(tulip.is_complete());
+        assert_eq!(find("no_such_plant").id, DEFAULT_SPECIES);
+    }
+
+    #[test]
+    fn random_seeds_are_weighted_by_rarity() {
+        let total: u64 = SPECIES.iter().map(|s| s.rarity.weight()).sum();
+        for species in SPECIES {
+            let rolls = (0..total).filter(|&roll| weighted(roll).id == species.id).count() as u64;
+            assert_eq!(rolls, species.rarity.weight(), "{}", species.id);
+        }
+        assert_eq!(weighted(total - 1).rarity, Rarity::Legendary);
+    }
+}
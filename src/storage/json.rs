@@ -0,0 +1,226 @@
+use chrono::{DateTime, Local, NaiveDate};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::{day_bounds, newer_schema, parse_data, Data, SessionLog, Storage};
+
+const BACKUP_COUNT: usize = 5;
+const BACKUP_INTERVAL_SECS: u64 = 60 * 60;
+
+// The whole document in one pretty-printed file, rewritten on every save
+#[derive(Debug)]
+pub struct JsonStorage {
+    path: PathBuf,
+    sessions: Vec<SessionLog>, // as last loaded or saved, oldest first
+    unwritable: bool, // the file is from a newer taman, so saving would throw away what we can't read
+}
+
+impl JsonStorage {
+    pub fn new(path: PathBuf) -> Self {
+        JsonStorage { path, sessions: vec![], unwritable: false }
+    }
+
+    pub fn writable(&self) -> bool {
+        !self.unwritable
+    }
+
+    fn remember_sessions(&mut self, data: &Data) {
+        self.sessions = data.sessions.clone();
+        self.sessions.sort_by_key(|s| s.end_time);
+    }
+}
+
+impl Storage for JsonStorage {
+    fn load(&mut self) -> (Data, Option<String>) {
+        if let Some(version) = fs::read_to_string(&self.path).ok().and_then(|c| newer_schema(&c)) {
+            self.unwritable = true;
+            return (Data::default(), Some(format!(
+                "data.json is from a newer taman (schema version {}), nothing will be saved until taman is updated", version,
+            )));
+        }
+        let (data, notice) = load_data(&self.path);
+        self.remember_sessions(&data);
+        (data, notice)
+    }
+
+    fn save(&mut self, data: &Data) -> io::Result<()> {
+        if self.unwritable {
+            return Err(io::Error::other("data.json is from a newer taman"));
+        }
+        save_data(&self.path, data)?;
+        self.remember_sessions(data);
+        Ok(())
+    }
+
+    fn sessions_between(&self, from: NaiveDate, to: NaiveDate) -> io::Result<Vec<SessionLog>> {
+        let (start, end) = day_bounds(from, to);
+        let first = self.sessions.partition_point(|s| s.end_time < start);
+        let last = self.sessions.partition_point(|s| s.end_time < end);
+        Ok(self.sessions[first..last.max(first)].to_vec())
+    }
+}
+
+// data.json.1 is the newest backup, data.json.BACKUP_COUNT the oldest
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    path.with_extension(format!("json.{}", n))
+}
+
+fn read_data(path: &Path) -> Result<Data, String> {
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_data(&contents)
+}
+
+fn load_data(path: &Path) -> (Data, Option<String>) {
+    if !path.exists() {
+        return (Data::default(), None);
+    }
+    let err = match read_data(path) {
+        Ok(data) => return (data, None),
+        Err(err) => err,
+    };
+    // Keep the unreadable file around so nothing is lost for good
+    let corrupt_path = path.with_extension(format!("json.corrupt-{}", Local::now().format("%Y%m%d%H%M%S")));
+    let _ = fs::copy(path, &corrupt_path);
+    for n in 1..=BACKUP_COUNT {
+        let backup = backup_path(path, n);
+        if let Ok(data) = read_data(&backup) {
+            let saved_at = fs::metadata(&backup)
+                .and_then(|m| m.modified())
+                .map(|t| DateTime::<Local>::from(t).format("%d %b %Y %H:%M").to_string())
+                .unwrap_or("an unknown time".to_string());
+            return (data, Some(format!("data.json could not be read ({}), restored the backup from {}", err, saved_at)));
+        }
+    }
+    (Data::default(), Some(format!("data.json could not be read ({}) and no backup was usable, starting fresh. The old file is kept as {}", err, corrupt_path.display())))
+}
+
+fn save_data(path: &Path, data: &Data) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(data).map_err(io::Error::other)?;
+    rotate_backups(path);
+    // Write next to the real file and swap it in, so a crash never leaves half a file
+    let tmp_path = path.with_extension("json.tmp");
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)
+}
+
+fn rotate_backups(path: &Path) {
+    let newest_age = fs::metadata(backup_path(path, 1))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok());
+    if newest_age.is_some_and(|age| age < Duration::from_secs(BACKUP_INTERVAL_SECS)) {
+        return;
+    }
+    // Only a file that still loads is worth keeping as a backup
+    if read_data(path).is_err() {
+        return;
+    }
+    for n in (1..BACKUP_COUNT).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            let _ = fs::rename(&from, backup_path(path, n + 1));
+        }
+    }
+    let _ = fs::copy(path, backup_path(path, 1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh directory per test, so tests running in parallel don't share files
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("taman-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn leaves_files_from_newer_versions_alone() {
+        let dir = temp_dir("newer");
+        let path = dir.join("data.json");
+        let newer = format!("{{\"schema_version\": {}, \"sessions\": []}}", super::super::SCHEMA_VERSION + 1);
+        fs::write(&path, &newer).unwrap();
+        fs::write(backup_path(&path, 1), serde_json::to_string(&Data::default()).unwrap()).unwrap();
+
+        let mut storage = JsonStorage::new(path.clone());
+        let (_, notice) = storage.load();
+        assert!(notice.unwrap().contains("newer taman"));
+        assert!(storage.save(&Data::default()).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2); // no corrupt copy either
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn saved(points: u32) -> Data {
+        Data { growth_points: points, ..Data::default() }
+    }
+
+    fn points_in(path: &Path) -> u32 {
+        read_data(path).unwrap().growth_points
+    }
+
+    // Makes the newest backup look old enough to be rotated on the next save
+    fn age_newest_backup(path: &Path) {
+        let two_hours_ago = SystemTime::now() - Duration::from_secs(2 * BACKUP_INTERVAL_SECS);
+        fs::File::options().write(true).open(backup_path(path, 1)).unwrap().set_modified(two_hours_ago).unwrap();
+    }
+
+    #[test]
+    fn saves_through_a_temp_file() {
+        let dir = temp_dir("tmp-write");
+        let path = dir.join("data.json");
+        fs::write(path.with_extension("json.tmp"), "left over from a crash").unwrap();
+        save_data(&path, &saved(1)).unwrap();
+        assert_eq!(points_in(&path), 1);
+        assert!(!path.with_extension("json.tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotates_backups_at_most_once_an_hour() {
+        let dir = temp_dir("rotation");
+        let path = dir.join("data.json");
+        save_data(&path, &saved(1)).unwrap();
+        assert!(!backup_path(&path, 1).exists()); // nothing to back up yet
+        save_data(&path, &saved(2)).unwrap();
+        assert_eq!(points_in(&backup_path(&path, 1)), 1);
+        save_data(&path, &saved(3)).unwrap();
+        assert_eq!(points_in(&backup_path(&path, 1)), 1); // the backup is still fresh
+
+        for points in 4..=10 {
+            age_newest_backup(&path);
+            save_data(&path, &saved(points)).unwrap();
+        }
+        assert_eq!(points_in(&backup_path(&path, 1)), 9);
+        assert_eq!(points_in(&backup_path(&path, BACKUP_COUNT)), 5);
+        assert!(!backup_path(&path, BACKUP_COUNT + 1).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restores_the_newest_backup_that_loads() {
+        let dir = temp_dir("restore");
+        let path = dir.join("data.json");
+        fs::write(&path, "{ not json").unwrap();
+        fs::write(backup_path(&path, 1), "").unwrap();
+        fs::write(backup_path(&path, 2), serde_json::to_string(&saved(7)).unwrap()).unwrap();
+        fs::write(backup_path(&path, 3), serde_json::to_string(&saved(3)).unwrap()).unwrap();
+
+        let (data, notice) = load_data(&path);
+        assert_eq!(data.growth_points, 7);
+        assert!(notice.unwrap().contains("restored the backup"));
+        let corrupt = fs::read_dir(&dir).unwrap().filter_map(|e| e.ok())
+            .find(|e| e.file_name().to_string_lossy().starts_with("data.json.corrupt-"))
+            .unwrap();
+        assert_eq!(fs::read_to_string(corrupt.path()).unwrap(), "{ not json");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
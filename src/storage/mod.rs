@@ -0,0 +1,517 @@
+use chrono::{DateTime, Local, NaiveDate};
+use crate::timer::{SessionType, TimerState};
+use crate::garden::CompletedPlant;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+mod json;
+mod sqlite;
+
+pub use json::JsonStorage;
+pub use sqlite::SqliteStorage;
+
+pub const SCHEMA_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionOutcome {
+    #[default]
+    Completed,
+    Abandoned,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionLog {
+    pub session_type: SessionType,
+    pub duration: u64,
+    pub end_time: DateTime<Local>,
+    #[serde(default)]
+    pub outcome: SessionOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<u32>, // the task a focus session was spent on
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActiveSession {
+    pub session_type: SessionType,
+    pub state: TimerState,
+    pub duration_seconds: u64,
+    pub started_at: DateTime<Local>,
+    pub paused_at: Option<DateTime<Local>>,
+    pub paused_seconds: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub focus_duration: u64, // in minutes
+    pub short_break_duration: u64,
+    pub long_break_duration: u64,
+    pub theme: String, // a theme name, built-in or from the themes folder
+    pub cycle_mode: bool,
+    pub long_break_interval: u32, // focus sessions per cycle
+    pub auto_start_breaks: bool,
+    pub auto_start_focus: bool,
+    pub growth: crate::plant::GrowthRules,
+    pub next_species: Option<String>, // None picks a random seed
+    pub strict_mode: bool,
+    pub wither_penalty: u32, // growth points lost per abandoned focus session
+    pub wither_strikes: u32, // abandoned focus sessions before the plant dies
+    pub hooks: crate::hooks::HookSettings,
+    pub notifications: crate::notify::NotificationSettings,
+    pub goals: crate::goals::GoalSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            focus_duration: 25,
+            short_break_duration: 5,
+            long_break_duration: 15,
+            theme: "System".to_string(),
+            cycle_mode: false,
+            long_break_interval: 4,
+            auto_start_breaks: true,
+            auto_start_focus: false,
+            growth: crate::plant::GrowthRules::default(),
+            next_species: None,
+            strict_mode: false,
+            wither_penalty: 2,
+            wither_strikes: 3,
+            hooks: crate::hooks::HookSettings::default(),
+            notifications: crate::notify::NotificationSettings::default(),
+            goals: crate::goals::GoalSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    // Bounds of the numeric settings. The Settings tab steps within them and `config set` refuses values outside.
+    fn limits(&self) -> [(&'static str, u64, RangeInclusive<u64>); 10] {
+        let (daily, weekly) = match self.goals.unit {
+            crate::goals::GoalUnit::Sessions => (0..=50, 0..=300),
+            crate::goals::GoalUnit::Minutes => (0..=1440, 0..=6000),
+        };
+        [
+            ("focus_duration", self.focus_duration, 1..=60),
+            ("short_break_duration", self.short_break_duration, 1..=60),
+            ("long_break_duration", self.long_break_duration, 1..=60),
+            ("long_break_interval", self.long_break_interval as u64, 1..=12),
+            ("growth.points", self.growth.points as u64, 1..=10),
+            ("growth.min_minutes", self.growth.min_minutes, 0..=60),
+            ("wither_penalty", self.wither_penalty as u64, 0..=10),
+            ("wither_strikes", self.wither_strikes as u64, 1..=10),
+            ("goals.daily", self.goals.daily, daily),
+            ("goals.weekly", self.goals.weekly, weekly),
+        ]
+    }
+
+    pub fn range(&self, key: &str) -> Option<RangeInclusive<u64>> {
+        self.limits().into_iter().find(|(name, _, _)| *name == key).map(|(_, _, range)| range)
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        match self.limits().into_iter().find(|(_, value, range)| !range.contains(value)) {
+            Some((key, value, range)) => Err(format!("{} must be between {} and {}, got {}", key, range.start(), range.end(), value)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Default)]
+pub struct Data {
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default = "default_species")]
+    pub current_species: String,
+    pub current_plant_stage: u32,
+    pub growth_points: u32,
+    #[serde(default)]
+    pub current_planted_at: Option<DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub current_plant_sessions: u32,
+    #[serde(default)]
+    pub current_plant_strikes: u32,
+    pub settings: Settings,
+    #[serde(default)]
+    pub sessions: Vec<SessionLog>, // full history, every statistic is derived from it
+    pub completed_plants: Vec<CompletedPlant>,
+    pub auto_run: Vec<crate::timer::SessionType>,
+    pub auto_run_index: Option<usize>,
+    #[serde(default)]
+    pub active_session: Option<ActiveSession>,
+    #[serde(default)]
+    pub cycle_completed: u32,
+    #[serde(default)]
+    pub last_streak: u32, // the streak as of the last save, to notice when it breaks
+    #[serde(default)]
+    pub tasks: crate::tasks::Tasks,
+}
+
+fn default_species() -> String {
+    crate::species::DEFAULT_SPECIES.to_string()
+}
+
+pub trait Storage: std::fmt::Debug {
+    // Returns the data plus a notice for the user when it had to be recovered
+    fn load(&mut self) -> (Data, Option<String>);
+    fn save(&mut self, data: &Data) -> io::Result<()>;
+    // Sessions that ended on any day from `from` to `to`, both included, oldest first
+    fn sessions_between(&self, from: NaiveDate, to: NaiveDate) -> io::Result<Vec<SessionLog>>;
+}
+
+pub fn data_dir() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("taman");
+    let _ = fs::create_dir_all(&path);
+    path
+}
+
+// TAMAN_STORAGE=sqlite or json picks the backend, otherwise an existing data.db wins.
+// The notice explains a fallback to JSON or a first import into SQLite.
+pub fn open() -> (Box<dyn Storage>, Option<String>) {
+    let db_path = data_dir().join("data.db");
+    let use_sqlite = match std::env::var("TAMAN_STORAGE").unwrap_or_default().to_lowercase().as_str() {
+        "sqlite" => true,
+        "json" => false,
+        _ => db_path.exists(),
+    };
+    if !use_sqlite {
+        return (Box::new(JsonStorage::new(data_dir().join("data.json"))), None);
+    }
+    match SqliteStorage::open(&db_path, &data_dir().join("data.json")) {
+        Ok((storage, notice)) => (Box::new(storage), notice),
+        Err(err) => (
+            Box::new(JsonStorage::new(data_dir().join("data.json"))),
+            Some(format!("data.db could not be opened ({}), using data.json instead", err)),
+        ),
+    }
+}
+
+// Held by a TUI that saves the data itself, so CLI commands don't write under it.
+// None while another process holds it; the lock is released with the file.
+pub fn lock_data() -> io::Result<Option<fs::File>> {
+    let file = fs::OpenOptions::new().create(true).truncate(false).write(true).open(data_dir().join("taman.lock"))?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(fs::TryLockError::WouldBlock) => Ok(None),
+        Err(fs::TryLockError::Error(err)) => Err(err),
+    }
+}
+
+// Local midnight at the start of `from` and at the end of `to`
+fn day_bounds(from: NaiveDate, to: NaiveDate) -> (DateTime<Local>, DateTime<Local>) {
+    let midnight = |date: NaiveDate| {
+        date.and_hms_opt(0, 0, 0).unwrap().and_local_timezone(Local).earliest().unwrap_or_else(Local::now)
+    };
+    (midnight(from), midnight(to + chrono::Days::new(1)))
+}
+
+pub fn parse_data(contents: &str) -> Result<Data, String> {
+    let value: Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    let value = migrate(value)?;
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+// The schema version of a document written by a newer taman. Such a file is left exactly
+// as it is, neither migrated nor replaced by a backup, since this build can't read all of it.
+pub fn newer_schema(contents: &str) -> Option<u64> {
+    let version = serde_json::from_str::<Value>(contents).ok()?.get("schema_version")?.as_u64()?;
+    (version > SCHEMA_VERSION as u64).then_some(version)
+}
+
+// MIGRATIONS[n] upgrades a version n document to version n + 1
+const MIGRATIONS: [fn(&mut Value); SCHEMA_VERSION as usize] = [migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
+
+fn migrate(mut value: Value) -> Result<Value, String> {
+    let version = value.get("schema_version").and_then(Value::as_u64).unwrap_or(0) as usize;
+    if version > SCHEMA_VERSION as usize {
+        return Err(format!("written by a newer taman (schema version {}, this build reads up to {})", version, SCHEMA_VERSION));
+    }
+    if !value.is_object() {
+        return Err("expected a JSON object".to_string());
+    }
+    for (from, step) in MIGRATIONS.iter().enumerate().skip(version) {
+        step(&mut value);
+        value["schema_version"] = Value::from(from + 1);
+    }
+    Ok(value)
+}
+
+fn insert_missing(object: &mut Value, key: &str, default: Value) {
+    if let Some(map) = object.as_object_mut() {
+        map.entry(key).or_insert(default);
+    }
+}
+
+// v0 covers every 0.1.x release: no schema_version and no serde defaults, so any
+// missing field is filled in rather than failing the whole load
+fn migrate_v0_to_v1(value: &mut Value) {
+    insert_missing(value, "current_plant_stage", json!(0));
+    insert_missing(value, "growth_points", json!(0));
+    insert_missing(value, "settings", json!({}));
+    insert_missing(value, "statistics", json!({}));
+    insert_missing(value, "completed_plants", json!([]));
+    insert_missing(value, "auto_run", json!([]));
+    insert_missing(value, "auto_run_index", Value::Null);
+
+    let statistics = &mut value["statistics"];
+    for key in ["total_sessions", "total_focus_sessions", "total_break_sessions", "total_minutes", "total_focus_minutes", "total_break_minutes", "completed_plants", "current_streak", "longest_streak"] {
+        insert_missing(statistics, key, json!(0));
+    }
+    for key in ["current_streak_start_date", "longest_streak_end_date"] {
+        insert_missing(statistics, key, Value::Null);
+    }
+    for key in ["current_streak_dates", "longest_streak_dates", "session_log", "recent_sessions", "recent_focus_sessions", "recent_break_sessions", "recent_plants", "recent_minutes", "recent_focus_minutes", "recent_break_minutes"] {
+        insert_missing(statistics, key, json!([]));
+    }
+}
+
+// v2 replaces the hand-maintained statistics with the session log alone. The log
+// used to be capped at 100 entries while the daily series were not, so sessions
+// the log had dropped are recreated from the series, one entry per missing session.
+fn migrate_v1_to_v2(value: &mut Value) {
+    let statistics = value.as_object_mut().and_then(|m| m.remove("statistics")).unwrap_or(json!({}));
+    let long_break = value["settings"]["long_break_duration"].as_u64().unwrap_or(15);
+    let date_of = |timestamp: &Value| {
+        timestamp.as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Local).date_naive())
+    };
+    // date -> (first timestamp seen that day, summed value)
+    let series = |key: &str| {
+        let mut days: BTreeMap<NaiveDate, (Value, u64)> = BTreeMap::new();
+        for entry in statistics[key].as_array().into_iter().flatten() {
+            if let Some(date) = date_of(&entry[0]) {
+                let day = days.entry(date).or_insert((entry[0].clone(), 0));
+                day.1 += entry[1].as_u64().unwrap_or(0);
+            }
+        }
+        days
+    };
+    let all_sessions = series("recent_sessions");
+    let all_minutes = series("recent_minutes");
+    let focus_sessions = series("recent_focus_sessions");
+    let focus_minutes = series("recent_focus_minutes");
+    let break_sessions = series("recent_break_sessions");
+    let break_minutes = series("recent_break_minutes");
+
+    let mut sessions: Vec<Value> = statistics["session_log"].as_array().cloned().unwrap_or_default();
+    let logged = |date: NaiveDate, types: &[&str]| {
+        sessions.iter()
+            .filter(|s| s["outcome"].as_str().unwrap_or("Completed") == "Completed")
+            .filter(|s| types.iter().any(|t| s["session_type"] == *t) && date_of(&s["end_time"]) == Some(date))
+            .fold((0, 0), |(count, minutes), s| (count + 1, minutes + s["duration"].as_u64().unwrap_or(0)))
+    };
+    let mut recreated = vec![];
+    let dates: BTreeSet<NaiveDate> = all_sessions.keys().chain(focus_sessions.keys()).chain(break_sessions.keys()).copied().collect();
+    for date in dates {
+        let get = |days: &BTreeMap<NaiveDate, (Value, u64)>| days.get(&date).map(|d| d.1).unwrap_or(0);
+        let (focus_count, focus_total) = (get(&focus_sessions), get(&focus_minutes));
+        let (break_count, break_total) = (get(&break_sessions), get(&break_minutes));
+        // Releases without the focus/break split only kept the totals, so those days
+        // come back as sessions of unknown type that neither focus nor break totals count
+        let (unknown_count, unknown_total) = match focus_count + break_count {
+            0 => (get(&all_sessions), get(&all_minutes)),
+            _ => (0, 0),
+        };
+        let timestamp = [&focus_sessions, &break_sessions, &all_sessions].iter()
+            .find_map(|days| days.get(&date).map(|d| d.0.clone()))
+            .unwrap_or(Value::Null);
+        let kinds: [(&[&str], u64, u64); 3] = [
+            (&["Focus"], focus_count, focus_total),
+            (&["ShortBreak", "LongBreak"], break_count, break_total),
+            (&["Focus", "ShortBreak", "LongBreak", "Unknown"], unknown_count, unknown_total),
+        ];
+        for (types, count, total) in kinds {
+            let (logged_count, logged_minutes) = logged(date, types);
+            let missing = count.saturating_sub(logged_count);
+            if missing == 0 {
+                continue;
+            }
+            let minutes = total.saturating_sub(logged_minutes);
+            let session_type = match types {
+                ["Focus"] => "Focus",
+                [_, _] if minutes / missing >= long_break => "LongBreak",
+                [_, _] => "ShortBreak",
+                _ => "Unknown",
+            };
+            for n in 0..missing {
+                // Spread the minutes evenly, the remainder goes to the first entries
+                let duration = minutes / missing + u64::from(n < minutes % missing);
+                recreated.push(json!({
+                    "session_type": session_type,
+                    "duration": duration,
+                    "end_time": timestamp,
+                    "outcome": "Completed",
+                }));
+            }
+        }
+    }
+    sessions.extend(recreated);
+    value["sessions"] = Value::Array(sessions);
+}
+
+// v3 stores the theme by its name, so themes loaded from files fit in too:
+// "RosePineDawn" becomes "Rose Pine Dawn". Old names go through ThemeVariant's own aliases.
+fn migrate_v2_to_v3(value: &mut Value) {
+    if let Some(theme) = value["settings"].get_mut("theme") {
+        if let Ok(variant) = serde_json::from_value::<crate::theme::ThemeVariant>(theme.clone()) {
+            *theme = json!(variant.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{History, SessionKind};
+
+    // Written by taman 0.1.3, the last release before schema versions
+    const V0_1_3: &str = include_str!("../../tests/fixtures/v0_1_3.json");
+    // Written by taman 0.1.3 after a one minute focus session, with Rose Pine Dawn and a queue set up
+    const V0_1_3_ROSE_PINE_DAWN: &str = include_str!("../../tests/fixtures/v0_1_3_rose_pine_dawn.json");
+
+    #[test]
+    fn migrates_v0_1_3_without_losing_history() {
+        let data = parse_data(V0_1_3).unwrap();
+        assert_eq!(data.schema_version, SCHEMA_VERSION);
+        let history = History::new(data.sessions.clone());
+        assert_eq!(history.total_sessions(SessionKind::All), 14);
+        assert_eq!(history.total_sessions(SessionKind::Focus), 10);
+        assert_eq!(history.total_minutes(SessionKind::Focus), 250);
+        assert_eq!(history.total_minutes(SessionKind::Break), 20);
+        assert_eq!(history.active_days().len(), 4);
+        assert_eq!(data.completed_plants.len(), 1);
+        assert_eq!(data.auto_run, vec![SessionType::Focus, SessionType::ShortBreak]);
+        assert_eq!(data.settings.focus_duration, 50);
+        assert_eq!(data.settings.theme, "Nord");
+        assert_eq!(data.current_species, crate::species::DEFAULT_SPECIES);
+    }
+
+    #[test]
+    fn migrates_theme_names_and_fills_missing_fields() {
+        let data = parse_data(V0_1_3_ROSE_PINE_DAWN).unwrap();
+        assert_eq!(data.schema_version, SCHEMA_VERSION);
+        assert_eq!(data.settings.theme, "Rose Pine Dawn");
+        assert_eq!(data.settings.short_break_duration, 1);
+        let history = History::new(data.sessions.clone());
+        assert_eq!(history.total_sessions(SessionKind::Focus), 1);
+        assert_eq!(history.total_minutes(SessionKind::Focus), 1);
+        assert_eq!(history.total_sessions(SessionKind::Break), 0);
+        assert_eq!(data.auto_run, vec![SessionType::Focus, SessionType::ShortBreak]);
+        assert_eq!(data.growth_points, 1);
+
+        let empty = parse_data("{}").unwrap();
+        assert_eq!(empty.settings, Settings::default());
+        assert!(empty.sessions.is_empty());
+    }
+
+    #[test]
+    fn current_documents_round_trip() {
+        let mut data = parse_data(V0_1_3).unwrap();
+        data.settings.strict_mode = true;
+        let json = serde_json::to_string(&data).unwrap();
+        let reloaded = parse_data(&json).unwrap();
+        assert_eq!(reloaded.schema_version, SCHEMA_VERSION);
+        assert!(reloaded.settings.strict_mode);
+        assert_eq!(reloaded.sessions.len(), data.sessions.len());
+    }
+
+    #[test]
+    fn keeps_logged_sessions_and_only_recreates_the_rest() {
+        let data = parse_data(V0_1_3).unwrap();
+        let history = History::new(data.sessions);
+        // 2025-11-12 is fully covered by the log, so nothing is made up for that day
+        let day = NaiveDate::from_ymd_opt(2025, 11, 12).unwrap();
+        let logged: Vec<_> = history.sessions.iter().filter(|s| s.end_time.date_naive() == day).collect();
+        assert_eq!(logged.len(), 3);
+        assert_eq!(logged[0].end_time.to_rfc3339(), "2025-11-12T09:25:03.118274+01:00".parse::<DateTime<Local>>().unwrap().to_rfc3339());
+        // Earlier days are rebuilt from the daily series
+        let earlier = NaiveDate::from_ymd_opt(2025, 11, 7).unwrap();
+        assert_eq!(history.daily_sessions(SessionKind::Focus)[0], (earlier, 3));
+        assert_eq!(history.daily_minutes(SessionKind::Focus)[0], (earlier, 75));
+        assert_eq!(history.daily_minutes(SessionKind::Break)[0], (earlier, 5));
+    }
+
+    #[test]
+    fn days_without_the_focus_split_are_of_unknown_type() {
+        let data = parse_data(r#"{"statistics": {
+            "recent_sessions": [["2025-10-01T10:00:00+02:00", 2]],
+            "recent_minutes": [["2025-10-01T10:00:00+02:00", 35]]
+        }}"#).unwrap();
+        assert_eq!(data.sessions.len(), 2);
+        assert!(data.sessions.iter().all(|s| s.session_type == SessionType::Unknown));
+        let history = History::new(data.sessions);
+        assert_eq!(history.total_minutes(SessionKind::All), 35);
+        assert_eq!(history.total_sessions(SessionKind::Focus), 0);
+        assert_eq!(history.total_sessions(SessionKind::Break), 0);
+    }
+
+    #[test]
+    fn sqlite_imports_data_json_and_saves_incrementally() {
+        let dir = std::env::temp_dir().join(format!("taman-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let json_path = dir.join("data.json");
+        let db_path = dir.join("data.db");
+        fs::write(&json_path, V0_1_3).unwrap();
+
+        let (mut storage, notice) = SqliteStorage::open(&db_path, &json_path).unwrap();
+        assert!(notice.unwrap().contains("Imported 14 sessions"));
+        let (mut data, _) = storage.load();
+        assert_eq!(data.sessions.len(), 14);
+        assert_eq!(data.settings.focus_duration, 50);
+        let day = NaiveDate::from_ymd_opt(2025, 11, 12).unwrap();
+        assert_eq!(storage.sessions_between(day, day).unwrap().len(), 3);
+
+        data.sessions.push(SessionLog {
+            session_type: SessionType::Focus,
+            duration: 25,
+            end_time: Local::now(),
+            outcome: SessionOutcome::Abandoned,
+            task: Some(1),
+        });
+        data.settings.strict_mode = true;
+        storage.save(&data).unwrap();
+        drop(storage);
+
+        // Opening an existing database never imports again
+        let (mut storage, notice) = SqliteStorage::open(&db_path, &json_path).unwrap();
+        assert!(notice.is_none());
+        let (reloaded, _) = storage.load();
+        assert_eq!(reloaded.sessions.len(), 15);
+        assert_eq!(reloaded.sessions[14].outcome, SessionOutcome::Abandoned);
+        assert_eq!(reloaded.sessions[14].task, Some(1));
+        assert_eq!(reloaded.sessions[0].task, None);
+        assert!(reloaded.settings.strict_mode);
+        assert_eq!(reloaded.completed_plants.len(), 1);
+        let today = Local::now().date_naive();
+        assert_eq!(storage.sessions_between(today, today).unwrap().len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_documents_from_newer_versions() {
+        let json = format!("{{\"schema_version\": {}}}", SCHEMA_VERSION + 1);
+        assert!(parse_data(&json).unwrap_err().contains("newer taman"));
+    }
+
+    #[test]
+    fn settings_outside_their_limits_are_refused() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.check(), Ok(()));
+        settings.focus_duration = 0;
+        assert_eq!(settings.check(), Err("focus_duration must be between 1 and 60, got 0".to_string()));
+        settings.focus_duration = 25;
+        settings.goals.daily = 120;
+        assert!(settings.check().is_err());
+        settings.goals.unit = crate::goals::GoalUnit::Minutes;
+        assert_eq!(settings.check(), Ok(()));
+    }
+}
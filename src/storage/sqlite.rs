@@ -0,0 +1,247 @@
+use chrono::{DateTime, Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{day_bounds, parse_data, Data, JsonStorage, SessionLog, Storage};
+use crate::garden::CompletedPlant;
+
+// Sessions and finished plants get a row each, everything else is one JSON
+// document under the `data` key, so settings keep using the migration chain
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sessions (
+        id INTEGER PRIMARY KEY,
+        session_type TEXT NOT NULL,
+        duration INTEGER NOT NULL,
+        end_time TEXT NOT NULL,
+        end_ms INTEGER NOT NULL,
+        outcome TEXT NOT NULL,
+        task INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS sessions_by_end ON sessions (end_ms);
+    CREATE TABLE IF NOT EXISTS completed_plants (
+        id INTEGER PRIMARY KEY,
+        species TEXT NOT NULL,
+        completed_at TEXT NOT NULL,
+        withered INTEGER NOT NULL,
+        plant TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+// Saves only write what changed since the last load or save
+#[derive(Debug)]
+pub struct SqliteStorage {
+    conn: Connection,
+    saved_sessions: usize,
+    last_saved_end: Option<DateTime<Local>>,
+    saved_plants: usize,
+    saved_state: String,
+    unreadable: bool, // loading failed, so saving would overwrite history we never saw
+}
+
+impl SqliteStorage {
+    // A new database is filled from data.json once, when there is one. The JSON file is left in place.
+    pub fn open(path: &Path, json_path: &Path) -> io::Result<(Self, Option<String>)> {
+        let is_new = !path.exists();
+        let conn = Connection::open(path).map_err(io::Error::other)?;
+        conn.execute_batch(SCHEMA).map_err(io::Error::other)?;
+        add_task_column(&conn).map_err(io::Error::other)?;
+        let mut storage = SqliteStorage {
+            conn,
+            saved_sessions: 0,
+            last_saved_end: None,
+            saved_plants: 0,
+            saved_state: String::new(),
+            unreadable: false,
+        };
+        if !is_new || !json_path.exists() {
+            return Ok((storage, None));
+        }
+        let mut json = JsonStorage::new(json_path.to_path_buf());
+        let (data, json_notice) = json.load();
+        let imported = match json.writable() {
+            true => Ok(()),
+            // Importing would start the database empty; better to stay on the JSON file and save nothing
+            false => Err(io::Error::other(json_notice.clone().unwrap_or_default())),
+        };
+        let imported = imported.and_then(|_| storage.save(&data)).and_then(|_| {
+            storage.conn.execute(
+                "INSERT OR REPLACE INTO state (key, value) VALUES ('imported_at', ?1)",
+                params![Local::now().to_rfc3339()],
+            ).map_err(io::Error::other)
+        });
+        if let Err(err) = imported {
+            // Without this the next start would find the database and never retry the import
+            drop(storage);
+            let _ = fs::remove_file(path);
+            return Err(err);
+        }
+        let notice = format!("Imported {} sessions and {} plants from data.json into data.db", data.sessions.len(), data.completed_plants.len());
+        Ok((storage, Some(match json_notice {
+            Some(json_notice) => format!("{}. {}", json_notice, notice),
+            None => notice,
+        })))
+    }
+
+    fn read(&self) -> Result<(Data, String), String> {
+        let state: Option<String> = self.conn
+            .query_row("SELECT value FROM state WHERE key = 'data'", [], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        let mut data = match &state {
+            Some(state) => parse_data(state)?,
+            None => Data::default(),
+        };
+        data.sessions = self.query_sessions("", [])?;
+
+        let mut statement = self.conn.prepare("SELECT plant FROM completed_plants ORDER BY id").map_err(|e| e.to_string())?;
+        let plants = statement.query_map([], |row| row.get::<_, String>(0)).map_err(|e| e.to_string())?;
+        for plant in plants {
+            let plant = plant.map_err(|e| e.to_string())?;
+            data.completed_plants.push(serde_json::from_str(&plant).map_err(|e| e.to_string())?);
+        }
+        Ok((data, state.unwrap_or_default()))
+    }
+
+    fn query_sessions(&self, filter: &str, params: impl rusqlite::Params) -> Result<Vec<SessionLog>, String> {
+        let sql = format!("SELECT session_type, duration, end_time, outcome, task FROM sessions {} ORDER BY end_ms, id", filter);
+        let mut statement = self.conn.prepare_cached(&sql).map_err(|e| e.to_string())?;
+        let rows = statement
+            .query_map(params, |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?, row.get::<_, Option<u32>>(4)?)))
+            .map_err(|e| e.to_string())?;
+        let mut sessions = vec![];
+        for row in rows {
+            let (session_type, duration, end_time, outcome, task) = row.map_err(|e| e.to_string())?;
+            sessions.push(SessionLog {
+                session_type: from_text(session_type)?,
+                duration: duration as u64,
+                end_time: DateTime::parse_from_rfc3339(&end_time).map_err(|e| e.to_string())?.with_timezone(&Local),
+                outcome: from_text(outcome)?,
+                task,
+            });
+        }
+        Ok(sessions)
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&mut self) -> (Data, Option<String>) {
+        match self.read() {
+            Ok((data, state)) => {
+                self.saved_sessions = data.sessions.len();
+                self.last_saved_end = data.sessions.last().map(|s| s.end_time);
+                self.saved_plants = data.completed_plants.len();
+                self.saved_state = state;
+                (data, None)
+            }
+            Err(err) => {
+                self.unreadable = true;
+                (Data::default(), Some(format!("data.db could not be read ({}), nothing will be saved until it is fixed", err)))
+            }
+        }
+    }
+
+    fn save(&mut self, data: &Data) -> io::Result<()> {
+        if self.unreadable {
+            return Err(io::Error::other("data.db could not be read at startup"));
+        }
+        let mut state = serde_json::to_value(data).map_err(io::Error::other)?;
+        state["sessions"] = json!([]);
+        state["completed_plants"] = json!([]);
+        let state = state.to_string();
+
+        let tx = self.conn.transaction().map_err(io::Error::other)?;
+        // New sessions normally land after the last stored one. Anything else
+        // (a clock that went backwards) rewrites the table.
+        let appended = self.saved_sessions <= data.sessions.len()
+            && (self.saved_sessions == 0 || data.sessions.get(self.saved_sessions - 1).map(|s| s.end_time) == self.last_saved_end);
+        let first_new_session = if appended {
+            self.saved_sessions
+        } else {
+            tx.execute("DELETE FROM sessions", []).map_err(io::Error::other)?;
+            0
+        };
+        {
+            let mut insert = tx
+                .prepare_cached("INSERT INTO sessions (session_type, duration, end_time, end_ms, outcome, task) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+                .map_err(io::Error::other)?;
+            for session in &data.sessions[first_new_session..] {
+                insert.execute(params![
+                    to_text(&session.session_type),
+                    session.duration as i64,
+                    session.end_time.to_rfc3339(),
+                    session.end_time.timestamp_millis(),
+                    to_text(&session.outcome),
+                    session.task,
+                ]).map_err(io::Error::other)?;
+            }
+        }
+
+        let first_new_plant = if self.saved_plants <= data.completed_plants.len() {
+            self.saved_plants
+        } else {
+            tx.execute("DELETE FROM completed_plants", []).map_err(io::Error::other)?;
+            0
+        };
+        {
+            let mut insert = tx
+                .prepare_cached("INSERT INTO completed_plants (species, completed_at, withered, plant) VALUES (?1, ?2, ?3, ?4)")
+                .map_err(io::Error::other)?;
+            for plant in &data.completed_plants[first_new_plant..] {
+                insert.execute(params![
+                    plant.plant.species,
+                    plant.completed_at.to_rfc3339(),
+                    plant.withered,
+                    serde_json::to_string::<CompletedPlant>(plant).map_err(io::Error::other)?,
+                ]).map_err(io::Error::other)?;
+            }
+        }
+
+        if state != self.saved_state {
+            tx.execute("INSERT OR REPLACE INTO state (key, value) VALUES ('data', ?1)", params![state])
+                .map_err(io::Error::other)?;
+        }
+        tx.commit().map_err(io::Error::other)?;
+
+        self.saved_sessions = data.sessions.len();
+        self.last_saved_end = data.sessions.last().map(|s| s.end_time);
+        self.saved_plants = data.completed_plants.len();
+        self.saved_state = state;
+        Ok(())
+    }
+
+    fn sessions_between(&self, from: NaiveDate, to: NaiveDate) -> io::Result<Vec<SessionLog>> {
+        let (start, end) = day_bounds(from, to);
+        self.query_sessions("WHERE end_ms >= ?1 AND end_ms < ?2", params![start.timestamp_millis(), end.timestamp_millis()])
+            .map_err(io::Error::other)
+    }
+}
+
+// Databases from before tasks have a sessions table without the column
+fn add_task_column(conn: &Connection) -> rusqlite::Result<()> {
+    let has_task: bool = conn.query_row("SELECT COUNT(*) FROM pragma_table_info('sessions') WHERE name = 'task'", [], |row| row.get(0))?;
+    if !has_task {
+        conn.execute("ALTER TABLE sessions ADD COLUMN task INTEGER", [])?;
+    }
+    Ok(())
+}
+
+// Enum values are stored by their serde names, the same text data.json uses
+fn to_text<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(text)) => text,
+        _ => String::new(),
+    }
+}
+
+fn from_text<T: DeserializeOwned>(text: String) -> Result<T, String> {
+    serde_json::from_value(Value::String(text)).map_err(|e| e.to_string())
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::{History, SessionKind};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Task {
+    pub id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub project: String, // empty when the task isn't part of a project
+    #[serde(default)]
+    pub estimate: u32, // in pomodoros, 0 when not estimated
+    #[serde(default)]
+    pub done: bool,
+}
+
+// Sessions point at tasks by id, so finished tasks are kept and ids never come back
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct Tasks {
+    pub list: Vec<Task>,
+    pub active: Option<u32>, // focus sessions are recorded against it
+    pub next_id: u32,
+}
+
+// Completed focus sessions and their minutes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Spent {
+    pub pomodoros: u32,
+    pub minutes: u64,
+}
+
+impl Tasks {
+    pub fn add(&mut self, name: &str, project: &str, estimate: u32) -> u32 {
+        let id = self.list.iter().map(|t| t.id + 1).chain([self.next_id, 1]).max().unwrap_or(1);
+        self.next_id = id + 1;
+        self.list.push(Task { id, name: name.trim().to_string(), project: project.trim().to_string(), estimate, done: false });
+        id
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Task> {
+        self.list.iter().find(|t| t.id == id)
+    }
+
+    // The task new focus sessions go to; a finished one no longer counts
+    pub fn active_task(&self) -> Option<&Task> {
+        self.active.and_then(|id| self.get(id)).filter(|t| !t.done)
+    }
+
+    pub fn set_done(&mut self, id: u32, done: bool) {
+        if let Some(task) = self.list.iter_mut().find(|t| t.id == id) {
+            task.done = done;
+        }
+        if done && self.active == Some(id) {
+            self.active = None;
+        }
+    }
+
+    pub fn open(&self) -> impl Iterator<Item = &Task> {
+        self.list.iter().filter(|t| !t.done)
+    }
+
+    // By id, or by name ignoring case, as typed on the command line
+    pub fn find(&self, text: &str) -> Option<&Task> {
+        let text = text.trim();
+        match text.trim_start_matches('#').parse::<u32>() {
+            Ok(id) => self.get(id),
+            Err(_) => self.list.iter().find(|t| t.name.eq_ignore_ascii_case(text)),
+        }
+    }
+
+    // Time per project, most first. Tasks without a project are grouped under "No project".
+    pub fn spent_by_project(&self, history: &History) -> Vec<(String, Spent)> {
+        let mut projects: BTreeMap<String, Spent> = BTreeMap::new();
+        for (id, spent) in spent_by_task(history) {
+            let Some(id) = id else { continue };
+            let project = match self.get(id) {
+                Some(task) if !task.project.is_empty() => task.project.clone(),
+                _ => "No project".to_string(),
+            };
+            let total = projects.entry(project).or_default();
+            total.pomodoros += spent.pomodoros;
+            total.minutes += spent.minutes;
+        }
+        let mut projects: Vec<(String, Spent)> = projects.into_iter().collect();
+        projects.sort_by_key(|(_, spent)| std::cmp::Reverse(spent.minutes));
+        projects
+    }
+}
+
+// Completed focus sessions per task, with the ones that had no task under None
+pub fn spent_by_task(history: &History) -> BTreeMap<Option<u32>, Spent> {
+    let mut tasks: BTreeMap<Option<u32>, Spent> = BTreeMap::new();
+    for session in history.completed(SessionKind::Focus) {
+        let spent = tasks.entry(session.task).or_default();
+        spent.pomodoros += 1;
+        spent.minutes += session.duration;
+    }
+    tasks
+}
+
+// "Write report +work ~4" is the task "Write report" in the project "work", estimated at 4 pomodoros
+pub fn parse_new(text: &str) -> Option<(String, String, u32)> {
+    let mut name = vec![];
+    let mut project = String::new();
+    let mut estimate = 0;
+    for word in text.split_whitespace() {
+        match (word.strip_prefix('+'), word.strip_prefix('~').and_then(|n| n.parse::<u32>().ok())) {
+            (Some(p), _) if !p.is_empty() => project = p.to_string(),
+            (_, Some(n)) => estimate = n,
+            _ => name.push(word),
+        }
+    }
+    (!name.is_empty()).then(|| (name.join(" "), project, estimate))
+}
+
+// What Enter does in the task picker
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskChoice {
+    NoTask,
+    Task(u32),
+    Add(String, String, u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TaskPicker {
+    pub query: String,
+    pub selected: usize, // index into the choices
+}
+
+impl TaskPicker {
+    // Open tasks matching the typed name, best first, then adding it as a new task.
+    // With nothing typed the list starts with "No task".
+    pub fn choices(&self, tasks: &Tasks) -> Vec<TaskChoice> {
+        let Some((name, project, estimate)) = parse_new(&self.query) else {
+            let mut choices = vec![TaskChoice::NoTask];
+            choices.extend(tasks.open().map(|t| TaskChoice::Task(t.id)));
+            return choices;
+        };
+        let mut scored: Vec<(i64, u32)> = tasks.open()
+            .filter_map(|t| crate::palette::fuzzy_score(&name, &format!("{} {}", t.name, t.project)).map(|score| (score, t.id)))
+            .collect();
+        scored.sort_by_key(|(score, _)| -score);
+        let mut choices: Vec<TaskChoice> = scored.into_iter().map(|(_, id)| TaskChoice::Task(id)).collect();
+        if !tasks.open().any(|t| t.name.eq_ignore_ascii_case(&name)) {
+            choices.push(TaskChoice::Add(name, project, estimate));
+        }
+        choices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{SessionLog, SessionOutcome};
+    use crate::timer::SessionType;
+    use chrono::Local;
+
+    #[test]
+    fn adds_tasks_from_text_and_adds_up_time_per_project() {
+        let mut tasks = Tasks::default();
+        assert_eq!(parse_new("Write report +work ~4"), Some(("Write report".to_string(), "work".to_string(), 4)));
+        assert_eq!(parse_new("+work ~4"), None);
+        let report = tasks.add("Write report", "work", 4);
+        let review = tasks.add("Review", "work", 2);
+        let reading = tasks.add("Reading", "", 0);
+        assert_eq!(tasks.find("review").map(|t| t.id), Some(review));
+        assert_eq!(tasks.find("#1").map(|t| t.id), Some(report));
+
+        let session = |task: Option<u32>, duration: u64, outcome: SessionOutcome| SessionLog {
+            session_type: SessionType::Focus,
+            duration,
+            end_time: Local::now(),
+            outcome,
+            task,
+        };
+        let history = History::new(vec![
+            session(Some(report), 25, SessionOutcome::Completed),
+            session(Some(report), 25, SessionOutcome::Completed),
+            session(Some(review), 10, SessionOutcome::Abandoned),
+            session(Some(reading), 30, SessionOutcome::Completed),
+            session(None, 25, SessionOutcome::Completed),
+        ]);
+        assert_eq!(spent_by_task(&history)[&Some(report)], Spent { pomodoros: 2, minutes: 50 });
+        assert_eq!(spent_by_task(&history).get(&Some(review)), None);
+        assert_eq!(tasks.spent_by_project(&history), vec![
+            ("work".to_string(), Spent { pomodoros: 2, minutes: 50 }),
+            ("No project".to_string(), Spent { pomodoros: 1, minutes: 30 }),
+        ]);
+
+        // Finishing the active task leaves no task active
+        tasks.active = Some(review);
+        tasks.set_done(review, true);
+        assert_eq!(tasks.active, None);
+        let picker = TaskPicker { query: "rev".to_string(), selected: 0 };
+        assert_eq!(picker.choices(&tasks), vec![TaskChoice::Add("rev".to_string(), String::new(), 0)]);
+        let picker = TaskPicker { query: "report".to_string(), selected: 0 };
+        assert_eq!(picker.choices(&tasks)[0], TaskChoice::Task(report));
+    }
+}
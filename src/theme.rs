@@ -22,6 +22,32 @@ pub enum ThemeVariant {
     CatppuccinMocha,
 }
 
+impl ThemeVariant {
+    // In the order the Settings tab cycles through them
+    pub const ALL: [ThemeVariant; 16] = [ThemeVariant::System, ThemeVariant::RosePineDawn, ThemeVariant::RosePine, ThemeVariant::GruvboxDark, ThemeVariant::GruvboxLight, ThemeVariant::SolarizedDark, ThemeVariant::SolarizedLight, ThemeVariant::Nord, ThemeVariant::TokyoNight, ThemeVariant::Monokai, ThemeVariant::Vesper, ThemeVariant::Everforest, ThemeVariant::CatppuccinLatte, ThemeVariant::CatppuccinFrappe, ThemeVariant::CatppuccinMacchiato, ThemeVariant::CatppuccinMocha];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeVariant::System => "System",
+            ThemeVariant::RosePineDawn => "Rose Pine Dawn",
+            ThemeVariant::RosePine => "Rose Pine",
+            ThemeVariant::GruvboxDark => "Gruvbox Dark",
+            ThemeVariant::GruvboxLight => "Gruvbox Light",
+            ThemeVariant::SolarizedDark => "Solarized Dark",
+            ThemeVariant::SolarizedLight => "Solarized Light",
+            ThemeVariant::Nord => "Nord",
+            ThemeVariant::TokyoNight => "Tokyo Night",
+            ThemeVariant::Monokai => "Monokai",
+            ThemeVariant::Vesper => "Vesper",
+            ThemeVariant::Everforest => "Everforest",
+            ThemeVariant::CatppuccinLatte => "Catppuccin Latte",
+            ThemeVariant::CatppuccinFrappe => "Catppuccin Frappé",
+            ThemeVariant::CatppuccinMacchiato => "Catppuccin Macchiato",
+            ThemeVariant::CatppuccinMocha => "Catppuccin Mocha",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Theme {
     pub tabs: Color,
@@ -301,4 +327,171 @@ impl Theme {
             },
         }
     }
-}
\ No newline at end of file
+}
+// ~/.config/taman/themes/dracula.toml:
+//   name = "Dracula"      # defaults to the file name
+//   base = "Tokyo Night"  # slots left out come from here, System by default
+//   highlight = "#bd93f9"
+//   text = "white"
+//   secondary_text = 244
+#[derive(serde::Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    name: Option<String>,
+    base: Option<String>,
+    tabs: Option<toml::Value>,
+    blocks: Option<toml::Value>,
+    text: Option<toml::Value>,
+    secondary_text: Option<toml::Value>,
+    highlight: Option<toml::Value>,
+    timer_text: Option<toml::Value>,
+    gauge_running: Option<toml::Value>,
+    gauge_paused: Option<toml::Value>,
+    gauge_finished: Option<toml::Value>,
+    rose: Option<toml::Value>,
+    love: Option<toml::Value>,
+    foam: Option<toml::Value>,
+    pine: Option<toml::Value>,
+    vertical_value: Option<toml::Value>,
+}
+
+// "#rrggbb", a name such as "light blue", or an index into the 256-color palette
+fn parse_color(slot: &str, value: &toml::Value) -> Result<Color, String> {
+    let invalid = || format!("`{}` = {} is not a color, use \"#rrggbb\", a name like \"light blue\" or a number from 0 to 255", slot, value);
+    match value {
+        toml::Value::Integer(n) => u8::try_from(*n).map(Color::Indexed).map_err(|_| invalid()),
+        toml::Value::String(text) => text.parse::<Color>().map_err(|_| invalid()),
+        _ => Err(invalid()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NamedTheme {
+    pub name: String,
+    pub theme: Theme,
+}
+
+// The built-in themes followed by the ones in the themes folder
+#[derive(Debug, Clone)]
+pub struct Themes {
+    pub list: Vec<NamedTheme>,
+}
+
+impl Default for Themes {
+    fn default() -> Self {
+        Themes {
+            list: ThemeVariant::ALL.iter().map(|v| NamedTheme { name: v.name().to_string(), theme: Theme::new(*v) }).collect(),
+        }
+    }
+}
+
+impl Themes {
+    // Returns the themes plus a message for every theme file that couldn't be used
+    pub fn load() -> (Self, Vec<String>) {
+        let mut themes = Themes::default();
+        let mut problems = vec![];
+        let dir = crate::storage::data_dir().join("themes");
+        let mut paths: Vec<_> = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.extension().is_some_and(|x| x == "toml")).collect(),
+            Err(_) => return (themes, problems),
+        };
+        paths.sort();
+        for path in paths {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| themes.parse(&stem, &text));
+            match result {
+                Ok(theme) => themes.list.push(theme),
+                Err(err) => problems.push(format!("themes/{}: {}", file_name, err)),
+            }
+        }
+        (themes, problems)
+    }
+
+    fn parse(&self, default_name: &str, text: &str) -> Result<NamedTheme, String> {
+        let file: ThemeFile = toml::from_str(text).map_err(|e| e.message().to_string())?;
+        let name = file.name.clone().unwrap_or(default_name.to_string());
+        if name.trim().is_empty() {
+            return Err("the theme name is empty".to_string());
+        }
+        if let Some(existing) = self.find(&name) {
+            return Err(format!("there is already a theme called `{}`", existing.name));
+        }
+        let mut theme = match &file.base {
+            Some(base) => self.find(base).ok_or(format!("unknown base theme `{}`", base))?.theme,
+            None => Theme::new(ThemeVariant::System),
+        };
+        let slots = [
+            ("tabs", &file.tabs, &mut theme.tabs),
+            ("blocks", &file.blocks, &mut theme.blocks),
+            ("text", &file.text, &mut theme.text),
+            ("secondary_text", &file.secondary_text, &mut theme.secondary_text),
+            ("highlight", &file.highlight, &mut theme.highlight),
+            ("timer_text", &file.timer_text, &mut theme.timer_text),
+            ("gauge_running", &file.gauge_running, &mut theme.gauge_running),
+            ("gauge_paused", &file.gauge_paused, &mut theme.gauge_paused),
+            ("gauge_finished", &file.gauge_finished, &mut theme.gauge_finished),
+            ("rose", &file.rose, &mut theme.rose),
+            ("love", &file.love, &mut theme.love),
+            ("foam", &file.foam, &mut theme.foam),
+            ("pine", &file.pine, &mut theme.pine),
+            ("vertical_value", &file.vertical_value, &mut theme.vertical_value),
+        ];
+        for (slot, value, color) in slots {
+            if let Some(value) = value {
+                *color = parse_color(slot, value)?;
+            }
+        }
+        Ok(NamedTheme { name, theme })
+    }
+
+    // Case, spaces and accents don't matter, so `nord`, `RosePineDawn` and
+    // `catppuccin frappe` all find their theme
+    pub fn find(&self, name: &str) -> Option<&NamedTheme> {
+        let key = |text: &str| -> String {
+            text.chars().filter(|c| c.is_alphanumeric()).map(|c| if c == 'é' { 'e' } else { c }).flat_map(char::to_lowercase).collect()
+        };
+        self.list.iter().find(|t| key(&t.name) == key(name))
+    }
+
+    pub fn position(&self, name: &str) -> Option<usize> {
+        let found = self.find(name)?;
+        self.list.iter().position(|t| t.name == found.name)
+    }
+
+    // A theme that has gone missing falls back to System
+    pub fn theme(&self, name: &str) -> Theme {
+        self.find(name).map(|t| t.theme).unwrap_or(Theme::new(ThemeVariant::System))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_hex_named_and_indexed_colors_over_a_base() {
+        let text = "name = \"Dusk\"\nbase = \"nord\"\nhighlight = \"#bd93f9\"\ntext = \"light blue\"\nsecondary_text = 244\n";
+        let dusk = Themes::default().parse("dusk", text).unwrap();
+        assert_eq!(dusk.name, "Dusk");
+        assert_eq!(dusk.theme.highlight, Color::Rgb(0xbd, 0x93, 0xf9));
+        assert_eq!(dusk.theme.text, Color::LightBlue);
+        assert_eq!(dusk.theme.secondary_text, Color::Indexed(244));
+        assert_eq!(dusk.theme.tabs, Theme::new(ThemeVariant::Nord).tabs);
+    }
+
+    #[test]
+    fn explains_what_is_wrong_with_a_theme_file() {
+        let themes = Themes::default();
+        let error = |text: &str| themes.parse("broken", text).unwrap_err();
+        assert!(error("highlight = \"#12345\"").contains("`highlight` = \"#12345\" is not a color"));
+        assert!(error("tabs = 256").contains("`tabs` = 256 is not a color"));
+        assert!(error("hilight = \"red\"").contains("unknown field `hilight`"));
+        assert!(error("base = \"Dracula\"").contains("unknown base theme `Dracula`"));
+        assert!(error("name = \"Rose Pine\"").contains("already a theme called `Rose Pine`"));
+        assert_eq!(themes.find("catppuccin frappe").unwrap().name, "Catppuccin Frappé");
+        assert_eq!(themes.find("RosePineDawn").unwrap().name, "Rose Pine Dawn");
+    }
+}
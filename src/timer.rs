@@ -1,10 +1,16 @@
-use std::time::{Duration, Instant};
+use std::time::Instant;
+use chrono::{DateTime, Local, TimeDelta};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// A wall-clock gap this much larger than the monotonic gap between two ticks
+// means the machine was asleep rather than the event loop being slow.
+const SUSPEND_THRESHOLD_SECS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum TimerState {
     Idle,
     Running,
     Paused,
+    Suspended,
     Finished,
 }
 
@@ -13,6 +19,7 @@ pub enum SessionType {
     Focus,
     ShortBreak,
     LongBreak,
+    Unknown, // logged by releases that didn't tell focus and breaks apart; never timed
 }
 
 impl SessionType {
@@ -21,6 +28,16 @@ impl SessionType {
             SessionType::Focus => settings.focus_duration,
             SessionType::ShortBreak => settings.short_break_duration,
             SessionType::LongBreak => settings.long_break_duration,
+            SessionType::Unknown => 0,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionType::Focus => "Focus",
+            SessionType::ShortBreak => "Short Break",
+            SessionType::LongBreak => "Long Break",
+            SessionType::Unknown => "Session",
         }
     }
 }
@@ -33,7 +50,12 @@ pub struct Timer {
     pub remaining_seconds: u64,
     pub auto_run: Vec<SessionType>,
     pub auto_run_index: Option<usize>,
-    pub last_tick: Option<Instant>,
+    pub cycle_completed: u32, // focus sessions finished in the current Pomodoro cycle
+    pub started_at: Option<DateTime<Local>>,
+    pub paused_at: Option<DateTime<Local>>,
+    pub paused_total: TimeDelta,
+    pub suspend_gap: Option<TimeDelta>,
+    pub last_tick: Option<(Instant, DateTime<Local>)>,
 }
 
 impl Timer {
@@ -45,44 +67,89 @@ impl Timer {
             remaining_seconds: SessionType::Focus.duration_minutes(settings) * 60,
             auto_run: vec![],
             auto_run_index: None,
+            cycle_completed: 0,
+            started_at: None,
+            paused_at: None,
+            paused_total: TimeDelta::zero(),
+            suspend_gap: None,
             last_tick: None,
         }
     }
 
     pub fn start(&mut self) {
-        if self.state == TimerState::Idle || self.state == TimerState::Paused {
-            self.state = TimerState::Running;
-            self.last_tick = Some(Instant::now());
+        match self.state {
+            TimerState::Idle => self.start_at(Local::now()),
+            TimerState::Paused => self.resume(),
+            _ => {}
+        }
+    }
+
+    pub fn start_at(&mut self, now: DateTime<Local>) {
+        if self.duration_seconds == 0 {
+            return;
         }
+        self.state = TimerState::Running;
+        self.started_at = Some(now);
+        self.paused_at = None;
+        self.paused_total = TimeDelta::zero();
+        self.suspend_gap = None;
+        self.last_tick = None;
+        self.update_remaining(now);
     }
 
     pub fn pause(&mut self) {
+        self.pause_at(Local::now());
+    }
+
+    fn pause_at(&mut self, now: DateTime<Local>) {
         if self.state == TimerState::Running {
+            self.update_remaining(now);
             self.state = TimerState::Paused;
+            self.paused_at = Some(now);
             self.last_tick = None;
         }
     }
 
     pub fn resume(&mut self) {
         if self.state == TimerState::Paused {
-            self.state = TimerState::Running;
-            self.last_tick = Some(Instant::now());
+            self.unpause(Local::now());
         }
     }
 
     pub fn stop(&mut self) {
         self.state = TimerState::Idle;
         self.remaining_seconds = self.duration_seconds;
-        self.last_tick = None;
+        self.clear_clock();
+    }
+
+    // Suspend resolution: the time the machine slept counts towards the session.
+    pub fn count_gap(&mut self) {
+        if self.state == TimerState::Suspended {
+            self.state = TimerState::Running;
+            self.paused_at = None;
+            self.suspend_gap = None;
+            self.last_tick = None;
+        }
     }
 
+    // Suspend resolution: the session resumes where it was when the machine slept.
+    pub fn discard_gap(&mut self) {
+        self.discard_gap_at(Local::now());
+    }
 
+    fn discard_gap_at(&mut self, now: DateTime<Local>) {
+        if self.state == TimerState::Suspended {
+            self.suspend_gap = None;
+            self.unpause(now);
+        }
+    }
 
     pub fn set_session(&mut self, session_type: SessionType, settings: &crate::storage::Settings) {
         self.session_type = session_type;
         self.duration_seconds = session_type.duration_minutes(settings) * 60;
         self.remaining_seconds = self.duration_seconds;
         self.state = TimerState::Idle;
+        self.clear_clock();
     }
 
     pub fn switch_session(&mut self, session_type: SessionType, settings: &crate::storage::Settings) {
@@ -90,34 +157,267 @@ impl Timer {
         self.duration_seconds = session_type.duration_minutes(settings) * 60;
         self.remaining_seconds = self.duration_seconds;
         self.state = TimerState::Idle;
+        self.clear_clock();
     }
 
     pub fn tick(&mut self) -> bool { // returns true if session finished
-        if self.state == TimerState::Running {
-            if let Some(last) = self.last_tick {
-                let elapsed = last.elapsed();
-                if elapsed >= Duration::from_secs(1) {
-                    if self.remaining_seconds > 0 {
-                        self.remaining_seconds -= 1;
-                    }
-                    self.last_tick = Some(Instant::now());
-                    if self.remaining_seconds == 0 {
-                        self.state = TimerState::Finished;
-                        return true;
-                    }
-                }
+        self.tick_at(Local::now(), Instant::now())
+    }
+
+    pub fn tick_at(&mut self, now: DateTime<Local>, instant: Instant) -> bool {
+        if self.state != TimerState::Running {
+            return false;
+        }
+        // A session without length would finish on every tick
+        if self.duration_seconds == 0 {
+            self.stop();
+            return false;
+        }
+        if let Some((last_instant, last_wall)) = self.last_tick {
+            let wall_gap = now - last_wall;
+            let monotonic_gap = TimeDelta::from_std(instant.duration_since(last_instant)).unwrap_or(TimeDelta::MAX);
+            if wall_gap - monotonic_gap >= TimeDelta::seconds(SUSPEND_THRESHOLD_SECS) {
+                // Freeze the session at the last moment we saw the machine awake
+                // and wait for the user to decide what the gap means.
+                self.update_remaining(last_wall);
+                self.state = TimerState::Suspended;
+                self.paused_at = Some(last_wall);
+                self.suspend_gap = Some(wall_gap);
+                self.last_tick = None;
+                return false;
             }
         }
+        self.last_tick = Some((instant, now));
+        self.update_remaining(now);
+        if self.remaining_seconds == 0 {
+            self.state = TimerState::Finished;
+            return true;
+        }
         false
     }
 
+    // Seconds of the session that have actually run, excluding pauses.
+    pub fn elapsed_seconds(&self, now: DateTime<Local>) -> u64 {
+        match self.started_at {
+            Some(started) => {
+                let until = self.paused_at.unwrap_or(now);
+                (until - started - self.paused_total).num_seconds().max(0) as u64
+            }
+            None => 0,
+        }
+    }
+
+    // Wall-clock moment the session ends if it is not paused again.
+    pub fn deadline(&self) -> Option<DateTime<Local>> {
+        self.started_at.map(|started| started + self.paused_total + TimeDelta::seconds(self.duration_seconds as i64))
+    }
+
+    pub fn snapshot(&self) -> Option<crate::storage::ActiveSession> {
+        match self.state {
+            TimerState::Running | TimerState::Paused | TimerState::Suspended => Some(crate::storage::ActiveSession {
+                session_type: self.session_type,
+                state: self.state,
+                duration_seconds: self.duration_seconds,
+                started_at: self.started_at?,
+                paused_at: self.paused_at,
+                paused_seconds: self.paused_total.num_seconds(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn restore(&mut self, active: &crate::storage::ActiveSession) {
+        let now = Local::now();
+        self.session_type = active.session_type;
+        self.duration_seconds = active.duration_seconds;
+        self.state = active.state;
+        self.started_at = Some(active.started_at);
+        self.paused_at = active.paused_at;
+        self.paused_total = TimeDelta::seconds(active.paused_seconds);
+        self.suspend_gap = match active.state {
+            TimerState::Suspended => active.paused_at.map(|paused| now - paused),
+            _ => None,
+        };
+        self.last_tick = None;
+        self.update_remaining(now);
+    }
+
     pub fn progress(&self) -> f64 {
+        if self.duration_seconds == 0 {
+            return 0.0;
+        }
         1.0 - (self.remaining_seconds as f64 / self.duration_seconds as f64)
     }
 
+    // Records a finished session in the Pomodoro cycle and returns the session that follows it
+    pub fn advance_cycle(&mut self, finished: SessionType, settings: &crate::storage::Settings) -> SessionType {
+        let interval = settings.long_break_interval.max(1);
+        match finished {
+            SessionType::Focus => {
+                self.cycle_completed += 1;
+                if self.cycle_completed >= interval {
+                    SessionType::LongBreak
+                } else {
+                    SessionType::ShortBreak
+                }
+            }
+            SessionType::ShortBreak | SessionType::Unknown => SessionType::Focus,
+            SessionType::LongBreak => {
+                self.cycle_completed = 0;
+                SessionType::Focus
+            }
+        }
+    }
+
+    // Which Pomodoro of the cycle the current session belongs to, e.g. (3, 4)
+    pub fn cycle_position(&self, settings: &crate::storage::Settings) -> (u32, u32) {
+        let interval = settings.long_break_interval.max(1);
+        let current = match self.session_type {
+            SessionType::Focus => self.cycle_completed + 1,
+            _ => self.cycle_completed.max(1),
+        };
+        (current.min(interval), interval)
+    }
+
     pub fn add_to_auto_run(&mut self, session: SessionType) {
         self.auto_run.push(session);
     }
 
+    fn unpause(&mut self, now: DateTime<Local>) {
+        if let Some(paused) = self.paused_at.take() {
+            self.paused_total += now - paused;
+        }
+        self.state = TimerState::Running;
+        self.last_tick = None;
+        self.update_remaining(now);
+    }
+
+    fn update_remaining(&mut self, now: DateTime<Local>) {
+        self.remaining_seconds = self.duration_seconds.saturating_sub(self.elapsed_seconds(now));
+    }
+
+    fn clear_clock(&mut self) {
+        self.started_at = None;
+        self.paused_at = None;
+        self.paused_total = TimeDelta::zero();
+        self.suspend_gap = None;
+        self.last_tick = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn one_minute_sessions() -> crate::storage::Settings {
+        crate::storage::Settings { focus_duration: 1, short_break_duration: 1, ..Default::default() }
+    }
+
+    fn secs(n: i64) -> TimeDelta {
+        TimeDelta::seconds(n)
+    }
+
+    #[test]
+    fn catches_up_across_several_sessions() {
+        let settings = one_minute_sessions();
+        let mut timer = Timer::new(&settings);
+        let (t0, instant) = (Local::now(), Instant::now());
+        timer.start_at(t0);
+        // 150 seconds later: focus and break are over, the next focus is half done
+        let now = t0 + secs(150);
+        assert!(timer.tick_at(now, instant));
+        assert_eq!(timer.deadline(), Some(t0 + secs(60)));
+        let next = timer.advance_cycle(SessionType::Focus, &settings);
+        timer.switch_session(next, &settings);
+        timer.start_at(t0 + secs(60));
+        assert!(timer.tick_at(now, instant));
+        assert_eq!(timer.deadline(), Some(t0 + secs(120)));
+        timer.switch_session(SessionType::Focus, &settings);
+        timer.start_at(t0 + secs(120));
+        assert!(!timer.tick_at(now, instant));
+        assert_eq!(timer.remaining_seconds, 30);
+    }
+
+    #[test]
+    fn paused_time_does_not_count() {
+        let mut timer = Timer::new(&one_minute_sessions());
+        let (t0, instant) = (Local::now(), Instant::now());
+        timer.start_at(t0);
+        timer.pause_at(t0 + secs(20));
+        assert_eq!(timer.elapsed_seconds(t0 + secs(100)), 20);
+        timer.unpause(t0 + secs(100));
+        assert!(!timer.tick_at(t0 + secs(110), instant));
+        assert_eq!(timer.remaining_seconds, 30);
+        assert_eq!(timer.deadline(), Some(t0 + secs(140)));
+    }
+
+    #[test]
+    fn suspends_when_the_wall_clock_jumps_past_the_threshold() {
+        let (t0, instant) = (Local::now(), Instant::now());
+        let ticked = |jump: i64| {
+            let mut timer = Timer::new(&one_minute_sessions());
+            timer.start_at(t0);
+            timer.tick_at(t0 + secs(1), instant);
+            // One second on the monotonic clock, `jump` seconds on the wall clock
+            timer.tick_at(t0 + secs(1 + jump), instant + Duration::from_secs(1));
+            timer
+        };
+        assert_eq!(ticked(SUSPEND_THRESHOLD_SECS).state, TimerState::Running);
+        let timer = ticked(SUSPEND_THRESHOLD_SECS + 1);
+        assert_eq!(timer.state, TimerState::Suspended);
+        assert_eq!(timer.suspend_gap, Some(secs(SUSPEND_THRESHOLD_SECS + 1)));
+        assert_eq!(timer.remaining_seconds, 59); // frozen at the last tick before the gap
+    }
+
+    #[test]
+    fn the_gap_is_either_counted_or_discarded() {
+        let suspended = |t0: DateTime<Local>, instant: Instant| {
+            let mut timer = Timer::new(&one_minute_sessions());
+            timer.start_at(t0);
+            timer.tick_at(t0 + secs(10), instant);
+            timer.tick_at(t0 + secs(50), instant + Duration::from_secs(1));
+            assert_eq!(timer.state, TimerState::Suspended);
+            timer
+        };
+        let (t0, instant) = (Local::now(), Instant::now());
+
+        let mut counted = suspended(t0, instant);
+        counted.count_gap();
+        assert!(!counted.tick_at(t0 + secs(55), instant + Duration::from_secs(2)));
+        assert_eq!(counted.remaining_seconds, 5);
+
+        let mut discarded = suspended(t0, instant);
+        discarded.discard_gap_at(t0 + secs(50));
+        assert!(!discarded.tick_at(t0 + secs(55), instant + Duration::from_secs(2)));
+        assert_eq!(discarded.remaining_seconds, 45);
+        assert_eq!(discarded.suspend_gap, None);
+    }
 
-}
\ No newline at end of file
+    #[test]
+    fn every_nth_focus_session_is_followed_by_a_long_break() {
+        let settings = crate::storage::Settings { long_break_interval: 3, ..Default::default() };
+        let mut timer = Timer::new(&settings);
+        let mut order = vec![];
+        let mut session = SessionType::Focus;
+        for _ in 0..8 {
+            session = timer.advance_cycle(session, &settings);
+            order.push(session);
+        }
+        use SessionType::*;
+        assert_eq!(order, vec![ShortBreak, Focus, ShortBreak, Focus, LongBreak, Focus, ShortBreak, Focus]);
+        // The long break started a new cycle, one focus session into it by now
+        assert_eq!(timer.cycle_completed, 1);
+        timer.session_type = Focus;
+        assert_eq!(timer.cycle_position(&settings), (2, 3));
+    }
+
+    #[test]
+    fn sessions_without_length_never_run() {
+        let mut timer = Timer::new(&crate::storage::Settings { focus_duration: 0, ..Default::default() });
+        timer.start_at(Local::now());
+        assert_eq!(timer.state, TimerState::Idle);
+        assert!(!timer.tick());
+        assert_eq!(timer.progress(), 0.0);
+    }
+}
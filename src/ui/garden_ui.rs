@@ -0,0 +1,133 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+use chrono::{Local, NaiveDate};
+
+use crate::app::App;
+
+const CELL_WIDTH: u16 = 4;
+
+pub fn draw_garden(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(area);
+
+    let filter = match app.garden_month {
+        Some((year, month)) => NaiveDate::from_ymd_opt(year, month, 1)
+            .map(|d| d.format("%B %Y").to_string())
+            .unwrap_or_default(),
+        None => "All Plants".to_string(),
+    };
+
+    // Left: grid of completed plants
+    let grid_block = Block::default()
+        .title_top(Line::from(format!(" Garden · {} ", filter)).style(Style::default().fg(app.theme.blocks)).centered())
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .style(Style::default().fg(app.theme.blocks));
+    let grid_inner = grid_block.inner(chunks[0]);
+    f.render_widget(grid_block, chunks[0]);
+    let grid_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(grid_inner);
+
+    let columns = (grid_chunks[0].width / CELL_WIDTH).max(1) as usize;
+    app.garden_columns = columns;
+    let plants = app.garden_plants();
+    let selected = app.garden_selected.min(plants.len().saturating_sub(1));
+
+    if plants.is_empty() {
+        let empty = match app.garden_month {
+            Some(_) => format!("No plants finished in {}", filter),
+            None => "No plants grown yet".to_string(),
+        };
+        let para = Paragraph::new(empty)
+            .style(Style::default().fg(app.theme.secondary_text))
+            .alignment(Alignment::Center);
+        f.render_widget(para, grid_chunks[0]);
+    } else {
+        let visible_rows = grid_chunks[0].height.max(1) as usize;
+        let total_rows = plants.len().div_ceil(columns);
+        let offset = (selected / columns + 1).saturating_sub(visible_rows);
+        let lines: Vec<Line> = plants
+            .chunks(columns)
+            .enumerate()
+            .skip(offset)
+            .take(visible_rows)
+            .map(|(row, row_plants)| {
+                Line::from(row_plants.iter().enumerate().map(|(col, p)| {
+                    let icon = p.icon();
+                    if row * columns + col == selected {
+                        Span::styled(format!("[{}]", icon), Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD))
+                    } else {
+                        Span::styled(format!(" {} ", icon), Style::default().fg(app.theme.text))
+                    }
+                }).collect::<Vec<_>>())
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines), grid_chunks[0]);
+
+        if total_rows > visible_rows {
+            let mut scrollbar_state = ScrollbarState::new(total_rows.saturating_sub(visible_rows)).position(offset);
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some("▲"))
+                .end_symbol(Some("▼"))
+                .track_symbol(Some("│"))
+                .thumb_symbol("█")
+                .thumb_style(Style::default().fg(app.theme.text))
+                .track_style(Style::default().fg(app.theme.secondary_text))
+                .begin_style(Style::default().fg(app.theme.secondary_text))
+                .end_style(Style::default().fg(app.theme.secondary_text));
+            f.render_stateful_widget(scrollbar, chunks[0], &mut scrollbar_state);
+        }
+    }
+
+    let count = Paragraph::new(format!("Showing {} of {} plants", plants.len(), app.garden.completed_plants.len()))
+        .style(Style::default().fg(app.theme.secondary_text))
+        .alignment(Alignment::Center);
+    f.render_widget(count, grid_chunks[1]);
+
+    // Right: details of the selected plant
+    let details_block = Block::default()
+        .title_top(Line::from(" Plant Details ").style(Style::default().fg(app.theme.blocks)).centered())
+        .borders(Borders::ALL)
+        .padding(Padding::new(1, 1, 1, 0))
+        .style(Style::default().fg(app.theme.blocks));
+    let details: Vec<Line> = match plants.get(selected) {
+        Some(p) => {
+            let species = p.plant.species();
+            let planted = p.plant.planted_at
+                .map(|d| d.with_timezone(&Local).format("%d %b %Y %H:%M").to_string())
+                .unwrap_or("Unknown".to_string());
+            let finished = p.completed_at.with_timezone(&Local).format("%d %b %Y %H:%M").to_string();
+            let sessions = if p.plant.sessions > 0 || p.plant.planted_at.is_some() { p.plant.sessions.to_string() } else { "Unknown".to_string() };
+            vec![
+                Line::from(format!("{} {}", p.icon(), species.name)).style(Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD)),
+                Line::from(""),
+                detail_line(app, "Rarity", species.rarity.to_string()),
+                detail_line(app, "Status", if p.withered { "Withered".to_string() } else { "Fully grown".to_string() }),
+                detail_line(app, "Planted", planted),
+                detail_line(app, if p.withered { "Died" } else { "Finished" }, finished),
+                detail_line(app, "Sessions", sessions),
+            ]
+        }
+        None => vec![Line::from("Nothing selected").style(Style::default().fg(app.theme.secondary_text))],
+    };
+    let details_para = Paragraph::new(details)
+        .block(details_block)
+        .style(Style::default().fg(app.theme.text));
+    f.render_widget(details_para, chunks[1]);
+}
+
+fn detail_line(app: &App, label: &'static str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{}: ", label), Style::default().fg(app.theme.secondary_text)),
+        Span::styled(value, Style::default().fg(app.theme.text)),
+    ])
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::history::SessionKind;
+
+pub const WEEKS: i64 = 53;
+
+// The Monday of the oldest column; the newest column holds today
+pub fn first_day(today: NaiveDate) -> NaiveDate {
+    today.week(Weekday::Mon).first_day() - Duration::weeks(WEEKS - 1)
+}
+
+// The day under a cell, column by column and Monday at the top
+pub fn day_at(today: NaiveDate, column: u16, row: u16) -> Option<NaiveDate> {
+    let day = first_day(today) + Duration::days(column as i64 * 7 + row as i64);
+    (column < WEEKS as u16 && row < 7 && day <= today).then_some(day)
+}
+
+// Empty days are a dot. Levels 1-4 split the busiest day's minutes into quarters.
+fn level(minutes: u64, max: u64) -> usize {
+    if minutes == 0 || max == 0 {
+        0
+    } else {
+        ((minutes * 4).div_ceil(max) as usize).clamp(1, 4)
+    }
+}
+
+// Blends from the block color towards the running gauge color. Themes built on
+// named colors can't be blended, so they step through their accent colors.
+fn shade(app: &App, level: usize) -> Color {
+    match (app.theme.blocks, app.theme.gauge_running) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let t = 0.2 + 0.2 * level as f64;
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        _ => [app.theme.secondary_text, app.theme.foam, app.theme.pine, app.theme.gauge_running][level.clamp(1, 4) - 1],
+    }
+}
+
+pub fn draw_heatmap(f: &mut Frame, app: &mut App, area: Rect) {
+    let today = Local::now().date_naive();
+    let first = first_day(today);
+    let minutes: HashMap<NaiveDate, u64> = app.history.daily_minutes(SessionKind::Focus)
+        .into_iter()
+        .filter(|(date, _)| *date >= first)
+        .collect();
+    let max = minutes.values().copied().max().unwrap_or(0);
+    let total: u64 = minutes.values().sum();
+
+    let block = Block::default()
+        .title(Line::from(format!(" Focus Minutes, Last Year: {} ", total)).style(Style::default().fg(app.theme.blocks)))
+        .borders(Borders::ALL)
+        .style(Style::default().fg(app.theme.blocks))
+        .padding(Padding::new(1, 0, 1, 0));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [months_row, grid, _, legend] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(7),
+        Constraint::Length(1),
+        Constraint::Length(2),
+    ]).areas(inner);
+
+    // Month names above the column where each month starts
+    let mut months = vec![' '; WEEKS as usize + 3];
+    let mut last_month = None;
+    for column in 0..WEEKS {
+        let monday = first + Duration::weeks(column);
+        let month = (monday + Duration::days(6)).month();
+        if last_month.is_some_and(|m| m != month) && column + 3 <= WEEKS {
+            let name = (monday + Duration::days(6)).format("%b").to_string();
+            for (i, c) in name.chars().enumerate() {
+                months[column as usize + i] = c;
+            }
+        }
+        last_month = Some(month);
+    }
+    let months_line = format!("    {}", months.iter().collect::<String>());
+    f.render_widget(Paragraph::new(months_line).style(Style::default().fg(app.theme.secondary_text)), months_row);
+
+    let cursor = if app.stats_selected == 9 && app.focus == crate::app::Focus::Right { Some(app.heatmap_cursor) } else { None };
+    let rows: Vec<Line> = (0..7).map(|row| {
+        let label = match row {
+            0 => "Mon ",
+            2 => "Wed ",
+            4 => "Fri ",
+            _ => "    ",
+        };
+        let mut spans = vec![Span::styled(label, Style::default().fg(app.theme.secondary_text))];
+        for column in 0..WEEKS as u16 {
+            let Some(day) = day_at(today, column, row) else {
+                spans.push(Span::raw(" "));
+                continue;
+            };
+            let level = level(minutes.get(&day).copied().unwrap_or(0), max);
+            let mut style = match level {
+                0 => Style::default().fg(app.theme.secondary_text),
+                _ => Style::default().fg(shade(app, level)),
+            };
+            // A streak shows as a band of color behind its days
+            if app.garden.current_streak_dates.contains(&day) {
+                style = style.bg(app.theme.highlight);
+            } else if app.garden.longest_streak_dates.contains(&day) {
+                style = style.bg(app.theme.love);
+            }
+            if cursor == Some(day) {
+                style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+            }
+            spans.push(Span::styled(if level == 0 { "·" } else { "■" }, style));
+        }
+        Line::from(spans)
+    }).collect();
+    f.render_widget(Paragraph::new(rows), grid);
+    app.hit_areas.heatmap = Rect { x: grid.x + 4, y: grid.y, width: (WEEKS as u16).min(grid.width.saturating_sub(4)), height: grid.height };
+
+    let mut scale = vec![Span::styled("    Less ", Style::default().fg(app.theme.secondary_text)), Span::styled("· ", Style::default().fg(app.theme.secondary_text))];
+    for level in 1..=4 {
+        scale.push(Span::styled("■ ", Style::default().fg(shade(app, level))));
+    }
+    scale.push(Span::styled("More", Style::default().fg(app.theme.secondary_text)));
+    let streaks = Line::from(vec![
+        Span::raw("    "),
+        Span::styled(" ", Style::default().bg(app.theme.highlight)),
+        Span::styled(format!(" Current streak ({})   ", app.garden.current_streak), Style::default().fg(app.theme.secondary_text)),
+        Span::styled(" ", Style::default().bg(app.theme.love)),
+        Span::styled(format!(" Longest streak ({})", app.garden.longest_streak), Style::default().fg(app.theme.secondary_text)),
+    ]);
+    f.render_widget(Paragraph::new(vec![Line::from(scale), streaks]), legend);
+}
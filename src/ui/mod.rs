@@ -1,4 +1,21 @@
 pub mod timer_ui;
 pub mod plant_ui;
 pub mod stats_ui;
-pub mod settings_ui;
\ No newline at end of file
+pub mod settings_ui;
+pub mod garden_ui;
+pub mod heatmap_ui;
+pub mod overlay_ui;
+
+use ratatui::layout::Rect;
+
+// Where clickable things ended up on screen, recorded while drawing
+#[derive(Debug, Default, Clone)]
+pub struct HitAreas {
+    pub tabs: Vec<(Rect, u8)>, // tab header titles and their tab number
+    pub timer: Rect,
+    pub sessions: Rect, // inside the list borders, one row per session
+    pub auto_run: Rect,
+    pub settings_list: Rect,
+    pub settings_values: Rect,
+    pub heatmap: Rect, // one cell per day, a column per week
+}
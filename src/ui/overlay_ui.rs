@@ -0,0 +1,233 @@
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app::{App, Tab};
+use crate::input::InputAction;
+use crate::keymap::Keymap;
+use crate::palette::Palette;
+use crate::tasks::{TaskChoice, TaskPicker};
+use crate::timer::TimerState;
+
+// Everything a key does on the current tab, followed by the keys that work everywhere
+fn bindings(app: &App) -> Vec<(&'static str, InputAction)> {
+    let mut bindings = match app.tab {
+        _ if app.timer.state == TimerState::Suspended => vec![
+            ("Count the time asleep", InputAction::CountGap),
+            ("Discard the time asleep", InputAction::DiscardGap),
+            ("End the session", InputAction::EndSession),
+        ],
+        Tab::Timer => vec![
+            ("Start/pause the selected session", InputAction::Space),
+            ("Stop the session", InputAction::Stop),
+            ("Restart the session", InputAction::Reset),
+            ("Sessions / auto-run set", InputAction::Left),
+            ("", InputAction::Right),
+            ("Move up", InputAction::Up),
+            ("Move down", InputAction::Down),
+            ("Add to the auto-run set", InputAction::Enter),
+            ("Remove from the auto-run set", InputAction::Delete),
+        ],
+        Tab::Plant => vec![],
+        Tab::Stats if app.in_heatmap() => vec![
+            ("Week before", InputAction::Left),
+            ("Week after", InputAction::Right),
+            ("Day before", InputAction::Up),
+            ("Day after", InputAction::Down),
+            ("Back to the categories", InputAction::Enter),
+        ],
+        Tab::Stats => vec![
+            ("Previous category", InputAction::Up),
+            ("Next category", InputAction::Down),
+            ("Older period", InputAction::Left),
+            ("Newer period", InputAction::Right),
+            ("Shorter periods (day/week/month/year)", InputAction::PrevPeriod),
+            ("Longer periods", InputAction::NextPeriod),
+            ("Heatmap cursor", InputAction::Enter),
+        ],
+        Tab::Settings => vec![
+            ("Settings / adjust", InputAction::Left),
+            ("", InputAction::Right),
+            ("Previous setting / increase", InputAction::Up),
+            ("Next setting / decrease", InputAction::Down),
+        ],
+        Tab::Garden => vec![
+            ("Select a plant", InputAction::Left),
+            ("", InputAction::Right),
+            ("", InputAction::Up),
+            ("", InputAction::Down),
+            ("Previous month", InputAction::PrevPeriod),
+            ("Next month", InputAction::NextPeriod),
+        ],
+    };
+    bindings.extend([
+        ("Timer tab", InputAction::Tab(1)),
+        ("Plant tab", InputAction::Tab(2)),
+        ("Stats tab", InputAction::Tab(3)),
+        ("Settings tab", InputAction::Tab(4)),
+        ("Garden tab", InputAction::Tab(5)),
+        ("Pause/resume from any tab", InputAction::TogglePause),
+        ("Start a focus session", InputAction::StartSession(crate::timer::SessionType::Focus)),
+        ("Start a short break", InputAction::StartSession(crate::timer::SessionType::ShortBreak)),
+        ("Start a long break", InputAction::StartSession(crate::timer::SessionType::LongBreak)),
+        ("Clear the auto-run set", InputAction::ClearQueue),
+        ("Pick or add a task", InputAction::Tasks),
+        ("Command palette", InputAction::Palette),
+        ("This help", InputAction::Help),
+        ("Quit", InputAction::Quit),
+    ]);
+    bindings
+}
+
+fn popup(area: Rect, width: u16, height: u16) -> Rect {
+    let [popup] = Layout::vertical([Constraint::Length(height.min(area.height))]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::horizontal([Constraint::Length(width.min(area.width))]).flex(Flex::Center).areas(popup);
+    popup
+}
+
+pub fn draw_help(f: &mut Frame, app: &App, keymap: &Keymap, area: Rect) {
+    // A blank description continues the row above, e.g. "Select a plant  ←, →, ↑, ↓"
+    let mut rows: Vec<(&str, Vec<String>)> = vec![];
+    for (description, action) in bindings(app) {
+        let mut keys: Vec<String> = keymap.keys(action).iter().map(|k| k.label()).collect();
+        keys.dedup();
+        match rows.last_mut() {
+            Some((_, previous)) if description.is_empty() => previous.extend(keys),
+            _ => rows.push((description, keys)),
+        }
+    }
+    // Actions left without a key are still in the palette
+    rows.retain(|(_, keys)| !keys.is_empty());
+
+    let lines: Vec<Line> = rows.iter().map(|(description, keys)| {
+        Line::from(vec![
+            Span::styled(format!("{:>14}  ", keys.join(", ")), Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD)),
+            Span::styled(description.to_string(), Style::default().fg(app.theme.text)),
+        ])
+    }).collect();
+    let popup = popup(area, 60, lines.len() as u16 + 4);
+    let block = Block::default()
+        .title_top(Line::from(" Keys ").style(Style::default().fg(app.theme.blocks)).centered())
+        .title_bottom(Line::from(" Any key closes ").style(Style::default().fg(app.theme.secondary_text)).centered())
+        .borders(Borders::ALL)
+        .style(Style::default().fg(app.theme.blocks));
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+    let [body] = Layout::vertical([Constraint::Fill(1)]).margin(1).areas(inner);
+    f.render_widget(Paragraph::new(lines), body);
+}
+
+pub fn draw_palette(f: &mut Frame, app: &App, palette: &Palette, area: Rect) {
+    let commands = crate::palette::commands(&app.themes);
+    let matches = palette.matches(&commands);
+    let popup = popup(area, 56, 14);
+    let block = Block::default()
+        .title_top(Line::from(" Command Palette ").style(Style::default().fg(app.theme.blocks)).centered())
+        .title_bottom(Line::from(" Run [Enter] | Close [Esc] ").style(Style::default().fg(app.theme.secondary_text)).centered())
+        .borders(Borders::ALL)
+        .style(Style::default().fg(app.theme.blocks));
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+
+    let [input, list_area] = Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+    let prompt = Line::from(vec![
+        Span::styled(": ", Style::default().fg(app.theme.highlight)),
+        Span::styled(palette.query.clone(), Style::default().fg(app.theme.text)),
+        Span::styled("█", Style::default().fg(app.theme.secondary_text)),
+    ]);
+    f.render_widget(Paragraph::new(prompt), input);
+
+    // Scrolls just far enough to keep the selection in view
+    let visible = list_area.height as usize;
+    let first = palette.selected.saturating_sub(visible.saturating_sub(1));
+    let items: Vec<ListItem> = matches.iter().enumerate().skip(first).take(visible).map(|(i, &command)| {
+        let is_selected = i == palette.selected;
+        let text = if is_selected { format!("→ {}", commands[command].label) } else { format!("  {}", commands[command].label) };
+        let style = if is_selected {
+            Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.theme.text)
+        };
+        ListItem::new(text).style(style)
+    }).collect();
+    if items.is_empty() {
+        f.render_widget(Paragraph::new("  No matching command").style(Style::default().fg(app.theme.secondary_text)), list_area);
+    } else {
+        f.render_widget(List::new(items), list_area);
+    }
+}
+
+pub fn draw_tasks(f: &mut Frame, app: &App, picker: &TaskPicker, area: Rect) {
+    let choices = picker.choices(&app.tasks);
+    let spent = crate::tasks::spent_by_task(&app.history);
+    let popup = popup(area, 64, 16);
+    let block = Block::default()
+        .title_top(Line::from(" Tasks ").style(Style::default().fg(app.theme.blocks)).centered())
+        .title_bottom(Line::from(" Pick [Enter] | Done [Ctrl+D] | Close [Esc] ").style(Style::default().fg(app.theme.secondary_text)).centered())
+        .borders(Borders::ALL)
+        .style(Style::default().fg(app.theme.blocks));
+    let inner = block.inner(popup);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+
+    let [input, hint, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+    let prompt = Line::from(vec![
+        Span::styled("> ", Style::default().fg(app.theme.highlight)),
+        Span::styled(picker.query.clone(), Style::default().fg(app.theme.text)),
+        Span::styled("█", Style::default().fg(app.theme.secondary_text)),
+    ]);
+    f.render_widget(Paragraph::new(prompt), input);
+    f.render_widget(Paragraph::new("  New task: name +project ~pomodoros").style(Style::default().fg(app.theme.secondary_text)), hint);
+
+    let label = |choice: &TaskChoice| match choice {
+        TaskChoice::NoTask => "No task".to_string(),
+        TaskChoice::Add(name, project, estimate) => {
+            let mut label = format!("Add \"{}\"", name);
+            if !project.is_empty() {
+                label.push_str(&format!(" to {}", project));
+            }
+            if *estimate > 0 {
+                label.push_str(&format!(", {} pomodoros", estimate));
+            }
+            label
+        }
+        TaskChoice::Task(id) => {
+            let Some(task) = app.tasks.get(*id) else { return String::new() };
+            let done = spent.get(&Some(task.id)).map(|s| s.pomodoros).unwrap_or(0);
+            let mut label = task.name.clone();
+            if !task.project.is_empty() {
+                label.push_str(&format!(" · {}", task.project));
+            }
+            match task.estimate {
+                0 => label.push_str(&format!(" · {} pomodoros", done)),
+                estimate => label.push_str(&format!(" · {}/{} pomodoros", done, estimate)),
+            }
+            label
+        }
+    };
+    // Scrolls just far enough to keep the selection in view
+    let visible = list_area.height as usize;
+    let first = picker.selected.saturating_sub(visible.saturating_sub(1));
+    let items: Vec<ListItem> = choices.iter().enumerate().skip(first).take(visible).map(|(i, choice)| {
+        let is_selected = i == picker.selected;
+        let is_active = match choice {
+            TaskChoice::Task(id) => app.tasks.active == Some(*id),
+            TaskChoice::NoTask => app.tasks.active_task().is_none(),
+            TaskChoice::Add(..) => false,
+        };
+        let text = format!("{}{}{}", if is_selected { "→ " } else { "  " }, label(choice), if is_active { " (current)" } else { "" });
+        let style = if is_selected {
+            Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.theme.text)
+        };
+        ListItem::new(text).style(style)
+    }).collect();
+    f.render_widget(List::new(items), list_area);
+}
@@ -16,7 +16,8 @@ pub fn draw_plant(f: &mut Frame, app: &App, area: Rect) {
 
     // Left: Growing Plant
     let left_block = Block::default()
-        .title_top(Line::from(format!(" Growing {} ", app.plant.stage)).style(Style::default().fg(app.theme.blocks)).centered())
+        .title_top(Line::from(format!(" Growing {} ", app.plant.stage_art().name)).style(Style::default().fg(app.theme.blocks)).centered())
+        .title_bottom(Line::from(format!(" {} · {} ", app.plant.species().name, app.plant.species().rarity)).style(Style::default().fg(app.theme.secondary_text)).centered())
         .borders(Borders::ALL)
         .style(Style::default().fg(app.theme.blocks));
     f.render_widget(left_block, chunks[0]);
@@ -49,19 +50,13 @@ pub fn draw_plant(f: &mut Frame, app: &App, area: Rect) {
             Constraint::Length(1),
         ])
         .split(emoji_inner);
-    let emoji_para = Paragraph::new(app.plant.stage.icon())
+    let emoji_para = Paragraph::new(app.plant.stage.icon(app.plant.species()))
         .style(Style::default().fg(app.theme.blocks))
         .alignment(Alignment::Center);
     f.render_widget(emoji_para, emoji_vert[1]);
 
     // Progress bar
-    let progress = match app.plant.growth_points {
-        0..=1 => (app.plant.growth_points as f64 / 2.0 * 100.0) as u16,
-        2..=4 => ((app.plant.growth_points - 2) as f64 / 3.0 * 100.0) as u16,
-        5..=7 => ((app.plant.growth_points - 5) as f64 / 3.0 * 100.0) as u16,
-        8..=9 => ((app.plant.growth_points - 8) as f64 / 2.0 * 100.0) as u16,
-        _ => 100,
-    };
+    let progress = (app.plant.stage_progress() * 100.0) as u16;
     let gauge = Gauge::default()
         .block(Block::default().padding(Padding::horizontal(1)))
         .gauge_style(Style::default().fg(app.theme.gauge_running))
@@ -69,15 +64,21 @@ pub fn draw_plant(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(gauge, left_inner[2]);
 
     // Sessions to next
-    let next_stage = match app.plant.growth_points {
-        0..=1 => "Sprout",
-        2..=4 => "Seedling",
-        5..=7 => "Young Plant",
-        8..=9 => "Complete",
-        _ => "Complete",
+    let next_stage = app.plant.next_stage_art().map(|s| s.name).unwrap_or("Complete");
+    let points_needed = app.plant.points_to_next_stage();
+    let per_step = app.settings.growth.points.max(1);
+    let steps = points_needed.div_ceil(per_step);
+    let info = match app.settings.growth.mode {
+        crate::plant::GrowthMode::PerSession => format!("{} sessions to {}", steps, next_stage),
+        crate::plant::GrowthMode::PerMinute => format!("{} minutes to {}", steps, next_stage),
     };
-    let info = format!("{} sessions to {}", app.plant.sessions_to_next_stage(), next_stage);
-    let para = Paragraph::new(info)
+    let mut lines = vec![Line::from(info)];
+    if app.settings.strict_mode {
+        let strikes_left = app.settings.wither_strikes.saturating_sub(app.plant.strikes);
+        let health = format!("Health: {}{}", "♥".repeat(strikes_left as usize), "♡".repeat(app.plant.strikes.min(app.settings.wither_strikes) as usize));
+        lines.push(Line::from(health).style(Style::default().fg(app.theme.love)));
+    }
+    let para = Paragraph::new(lines)
         .style(Style::default().fg(app.theme.blocks))
         .alignment(Alignment::Center);
     f.render_widget(para, left_inner[3]);
@@ -96,14 +97,18 @@ pub fn draw_plant(f: &mut Frame, app: &App, area: Rect) {
         .split(chunks[1]);
 
     // Plants
-    let plants_text = "🪴".repeat(app.garden.total_completed());
+    let plants_text = app.garden.completed_plants.iter().map(|p| p.icon()).collect::<String>();
     let plants_para = Paragraph::new(plants_text)
         .style(Style::default().fg(app.theme.blocks))
         .block(Block::default().padding(Padding::horizontal(1)));
     f.render_widget(plants_para, right_inner[0]);
 
     // Total
-    let total = format!("Total fully grown plants: {}", app.garden.total_completed());
+    let total = if app.garden.total_withered() > 0 {
+        format!("Total fully grown plants: {} · Withered: {}", app.garden.total_completed(), app.garden.total_withered())
+    } else {
+        format!("Total fully grown plants: {}", app.garden.total_completed())
+    };
     let total_para = Paragraph::new(total)
         .style(Style::default().fg(app.theme.blocks))
         .block(Block::default().padding(Padding::horizontal(1)));
@@ -7,36 +7,38 @@ use ratatui::{
 };
 
 use crate::app::App;
+use crate::goals::GoalUnit;
+use crate::plant::GrowthMode;
 
-pub fn draw_settings(f: &mut Frame, app: &App, area: Rect) {
+pub fn draw_settings(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
 
     // Left: Settings list
-    let theme_name = match app.settings.theme {
-        crate::theme::ThemeVariant::System => "System",
-        crate::theme::ThemeVariant::RosePineDawn => "Rose Pine Dawn",
-        crate::theme::ThemeVariant::RosePine => "Rose Pine",
-        crate::theme::ThemeVariant::GruvboxDark => "Gruvbox Dark",
-        crate::theme::ThemeVariant::GruvboxLight => "Gruvbox Light",
-        crate::theme::ThemeVariant::SolarizedDark => "Solarized Dark",
-        crate::theme::ThemeVariant::SolarizedLight => "Solarized Light",
-        crate::theme::ThemeVariant::Nord => "Nord",
-        crate::theme::ThemeVariant::TokyoNight => "Tokyo Night",
-        crate::theme::ThemeVariant::Monokai => "Monokai",
-        crate::theme::ThemeVariant::Vesper => "Vesper",
-        crate::theme::ThemeVariant::Everforest => "Everforest",
-        crate::theme::ThemeVariant::CatppuccinLatte => "Catppuccin Latte",
-        crate::theme::ThemeVariant::CatppuccinFrappe => "Catppuccin Frappé",
-        crate::theme::ThemeVariant::CatppuccinMacchiato => "Catppuccin Macchiato",
-        crate::theme::ThemeVariant::CatppuccinMocha => "Catppuccin Mocha",
-    };
     let settings = [format!("Focus Duration: {} min", app.settings.focus_duration),
         format!("Short Break: {} min", app.settings.short_break_duration),
         format!("Long Break: {} min", app.settings.long_break_duration),
-        format!("Theme: {}", theme_name)];
+        format!("Theme: {}", app.settings.theme),
+        format!("Pomodoro Cycle: {}", on_off(app.settings.cycle_mode)),
+        format!("Long Break Every: {} focus", app.settings.long_break_interval),
+        format!("Auto-start Breaks: {}", on_off(app.settings.auto_start_breaks)),
+        format!("Auto-start Focus: {}", on_off(app.settings.auto_start_focus)),
+        format!("Growth: {}", growth_mode_name(app.settings.growth.mode)),
+        format!("Growth Points: {}", app.settings.growth.points),
+        format!("Minimum Length: {} min", app.settings.growth.min_minutes),
+        format!("Focus Grows Plant: {}", on_off(app.settings.growth.focus)),
+        format!("Short Breaks Grow Plant: {}", on_off(app.settings.growth.short_breaks)),
+        format!("Long Breaks Grow Plant: {}", on_off(app.settings.growth.long_breaks)),
+        format!("Next Seed: {}", next_species_name(app)),
+        format!("Strict Mode: {}", on_off(app.settings.strict_mode)),
+        format!("Wither Penalty: {} points", app.settings.wither_penalty),
+        format!("Strikes Before Withering: {}", app.settings.wither_strikes),
+        format!("Goals Count: {}", goal_unit_name(app.settings.goals.unit)),
+        format!("Daily Goal: {}", goal_text(app.settings.goals.daily)),
+        format!("Weekly Goal: {}", goal_text(app.settings.goals.weekly)),
+        format!("Streaks Need Daily Goal: {}", on_off(app.settings.goals.streak_needs_goal))];
     let items: Vec<ListItem> = settings
         .iter()
         .enumerate()
@@ -51,9 +53,11 @@ pub fn draw_settings(f: &mut Frame, app: &App, area: Rect) {
             ListItem::new(text).style(style)
         })
         .collect();
-    let list = List::new(items)
-        .block(Block::default().title_top(Line::from(" Settings ").style(Style::default().fg(app.theme.blocks)).centered()).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)));
-    f.render_widget(list, chunks[0]);
+    let block = Block::default().title_top(Line::from(" Settings ").style(Style::default().fg(app.theme.blocks)).centered()).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks));
+    app.hit_areas.settings_list = block.inner(chunks[0]);
+    let list = List::new(items).block(block);
+    app.settings_list_state.select(Some(app.settings_selected));
+    f.render_stateful_widget(list, chunks[0], &mut app.settings_list_state);
 
     // Right: Adjustment
     let is_focused = app.focus == crate::app::Focus::Right;
@@ -92,38 +96,140 @@ pub fn draw_settings(f: &mut Frame, app: &App, area: Rect) {
             ])).style(style)]
         }
         3 => {
-            let themes = ["System", "Rose Pine Dawn", "Rose Pine", "Gruvbox Dark", "Gruvbox Light", "Solarized Dark", "Solarized Light", "Nord", "Tokyo Night", "Monokai", "Vesper", "Everforest", "Catppuccin Latte", "Catppuccin Frappé", "Catppuccin Macchiato", "Catppuccin Mocha"];
-            let current_index = match app.settings.theme {
-                crate::theme::ThemeVariant::System => 0,
-                crate::theme::ThemeVariant::RosePineDawn => 1,
-                crate::theme::ThemeVariant::RosePine => 2,
-                crate::theme::ThemeVariant::GruvboxDark => 3,
-                crate::theme::ThemeVariant::GruvboxLight => 4,
-                crate::theme::ThemeVariant::SolarizedDark => 5,
-                crate::theme::ThemeVariant::SolarizedLight => 6,
-                crate::theme::ThemeVariant::Nord => 7,
-                crate::theme::ThemeVariant::TokyoNight => 8,
-                crate::theme::ThemeVariant::Monokai => 9,
-                crate::theme::ThemeVariant::Vesper => 10,
-                crate::theme::ThemeVariant::Everforest => 11,
-                crate::theme::ThemeVariant::CatppuccinLatte => 12,
-                crate::theme::ThemeVariant::CatppuccinFrappe => 13,
-                crate::theme::ThemeVariant::CatppuccinMacchiato => 14,
-                crate::theme::ThemeVariant::CatppuccinMocha => 15,
-            };
-            themes.iter().enumerate().map(|(i, &theme)| {
+            let current_index = app.themes.position(&app.settings.theme).unwrap_or(0);
+            app.themes.list.iter().enumerate().map(|(i, theme)| {
                 let prefix = if i == current_index { "→ " } else { "  " };
                 let style = if i == current_index && is_focused {
                     Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD)
                 } else {
                     Style::default().fg(app.theme.text)
                 };
-                ListItem::new(format!("{}{}", prefix, theme)).style(style)
+                ListItem::new(format!("{}{}", prefix, theme.name)).style(style)
             }).collect()
         }
+        4 => vec![toggle_item(app, app.settings.cycle_mode, is_focused)],
+        5 => vec![value_item(app, app.settings.long_break_interval.to_string(), " focus sessions", is_focused)],
+        6 => vec![toggle_item(app, app.settings.auto_start_breaks, is_focused)],
+        7 => vec![toggle_item(app, app.settings.auto_start_focus, is_focused)],
+        8 => {
+            let modes = [GrowthMode::PerSession, GrowthMode::PerMinute];
+            modes.iter().map(|&mode| {
+                let is_current = mode == app.settings.growth.mode;
+                let prefix = if is_current { "→ " } else { "  " };
+                let style = if is_current && is_focused {
+                    Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(app.theme.text)
+                };
+                ListItem::new(format!("{}{}", prefix, growth_mode_name(mode))).style(style)
+            }).collect()
+        }
+        9 => {
+            let unit = match app.settings.growth.mode {
+                GrowthMode::PerSession => " points per session",
+                GrowthMode::PerMinute => " points per minute",
+            };
+            vec![value_item(app, app.settings.growth.points.to_string(), unit, is_focused)]
+        }
+        10 => vec![value_item(app, app.settings.growth.min_minutes.to_string(), " min", is_focused)],
+        11 => vec![toggle_item(app, app.settings.growth.focus, is_focused)],
+        12 => vec![toggle_item(app, app.settings.growth.short_breaks, is_focused)],
+        13 => vec![toggle_item(app, app.settings.growth.long_breaks, is_focused)],
+        14 => {
+            let current = next_species_name(app);
+            std::iter::once(("Random".to_string(), "🎲".to_string()))
+                .chain(crate::species::SPECIES.iter().map(|s| (s.name.to_string(), format!("{} {}", s.icon(), s.rarity))))
+                .map(|(name, detail)| {
+                    let is_current = name == current;
+                    let prefix = if is_current { "→ " } else { "  " };
+                    let style = if is_current && is_focused {
+                        Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(app.theme.text)
+                    };
+                    ListItem::new(format!("{}{} {}", prefix, name, detail)).style(style)
+                })
+                .collect()
+        }
+        15 => vec![toggle_item(app, app.settings.strict_mode, is_focused)],
+        16 => vec![value_item(app, app.settings.wither_penalty.to_string(), " growth points", is_focused)],
+        17 => vec![value_item(app, app.settings.wither_strikes.to_string(), " abandoned sessions", is_focused)],
+        18 => {
+            let units = [GoalUnit::Sessions, GoalUnit::Minutes];
+            units.iter().map(|&unit| {
+                let is_current = unit == app.settings.goals.unit;
+                let prefix = if is_current { "→ " } else { "  " };
+                let style = if is_current && is_focused {
+                    Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(app.theme.text)
+                };
+                ListItem::new(format!("{}{}", prefix, goal_unit_name(unit))).style(style)
+            }).collect()
+        }
+        19 => vec![value_item(app, goal_text(app.settings.goals.daily), goal_unit_suffix(app, app.settings.goals.daily, false), is_focused)],
+        20 => vec![value_item(app, goal_text(app.settings.goals.weekly), goal_unit_suffix(app, app.settings.goals.weekly, true), is_focused)],
+        21 => vec![toggle_item(app, app.settings.goals.streak_needs_goal, is_focused)],
         _ => vec![],
     };
     let right_list = List::new(right_items)
         .block(Block::default().title_top(Line::from(" Adjust ").style(Style::default().fg(app.theme.blocks)).centered()).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)));
     f.render_widget(right_list, chunks[1]);
-}
\ No newline at end of file
+    app.hit_areas.settings_values = chunks[1];
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "On" } else { "Off" }
+}
+
+fn next_species_name(app: &App) -> String {
+    match &app.settings.next_species {
+        Some(id) => crate::species::find(id).name.to_string(),
+        None => "Random".to_string(),
+    }
+}
+
+fn growth_mode_name(mode: GrowthMode) -> &'static str {
+    match mode {
+        GrowthMode::PerSession => "Per Session",
+        GrowthMode::PerMinute => "Per Minute",
+    }
+}
+
+fn goal_unit_name(unit: GoalUnit) -> &'static str {
+    match unit {
+        GoalUnit::Sessions => "Sessions",
+        GoalUnit::Minutes => "Minutes",
+    }
+}
+
+// A goal of 0 is switched off
+fn goal_text(goal: u64) -> String {
+    if goal == 0 { "Off".to_string() } else { goal.to_string() }
+}
+
+fn goal_unit_suffix(app: &App, goal: u64, weekly: bool) -> &'static str {
+    match (goal, app.settings.goals.unit, weekly) {
+        (0, _, _) => "",
+        (_, GoalUnit::Sessions, false) => " focus sessions a day",
+        (_, GoalUnit::Sessions, true) => " focus sessions a week",
+        (_, GoalUnit::Minutes, false) => " focus minutes a day",
+        (_, GoalUnit::Minutes, true) => " focus minutes a week",
+    }
+}
+
+fn value_item(app: &App, value: String, unit: &'static str, is_focused: bool) -> ListItem<'static> {
+    let mut style = Style::default().fg(app.theme.text);
+    if is_focused {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    ListItem::new(Line::from(vec![
+        Span::styled("  ", style),
+        Span::styled(value, Style::default().fg(app.theme.highlight)),
+        Span::styled(unit, style),
+    ])).style(style)
+}
+
+fn toggle_item(app: &App, value: bool, is_focused: bool) -> ListItem<'static> {
+    value_item(app, on_off(value).to_string(), "", is_focused)
+}
@@ -1,7 +1,7 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    text::Line,
+    text::{Line, Span},
     widgets::{Bar, BarChart, BarGroup, Block, Borders, List, ListItem, Padding, Paragraph},
     Frame,
 };
@@ -9,10 +9,11 @@ use tui_piechart::{PieChart, PieSlice, symbols};
 use std::env;
 
 use crate::app::App;
-use chrono::{DateTime, Local};
+use crate::history::{daily_plants, group, History, Period, SessionKind};
+use chrono::{Local, NaiveDate};
 use tui_big_text::{BigText, PixelSize};
 
-pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
+pub fn draw_stats(f: &mut Frame, app: &mut App, area: Rect) {
     let date_format = if env::var("LANG").unwrap_or_default().contains("US")
         || env::var("LC_TIME").unwrap_or_default().contains("US")
     {
@@ -20,41 +21,73 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
     } else {
         "%d/%m"
     };
-    let max_minute_value = [
-        &app.statistics.recent_minutes,
-        &app.statistics.recent_focus_minutes,
-        &app.statistics.recent_break_minutes,
-    ].iter().flat_map(|v| v.iter().map(|(_, m)| *m)).max().unwrap_or(0);
-    let max_y = (max_minute_value as f64 / 10.0).ceil() * 10.0;
+    app.hit_areas.heatmap = Rect::default();
+    let history = &app.history;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(area);
 
+    // Every chart has one bar per period. The newest bar is the period picked with
+    // ←/→ and older ones fill the chart, going no further back than the first session.
+    let today = Local::now().date_naive();
+    let period = app.stats_period;
+    let shown = app.stats_period_start();
+    let first_day = app.stats_first_day();
+    let upright_slots = (chunks[1].width.saturating_sub(3) / 6).max(1) as i64; // 5 wide plus a gap
+    let sideways_slots = chunks[1].height.saturating_sub(3).max(1) as i64;
+    let window = |series: &[(NaiveDate, u64)], slots: i64| {
+        let from = period.shift(shown, 1 - slots).max(period.start(first_day));
+        group(series, period, from, shown)
+    };
+    let label = |date: &NaiveDate| match period {
+        Period::Day | Period::Week => date.format(date_format).to_string(),
+        Period::Month => date.format("%b").to_string(),
+        Period::Year => date.format("%Y").to_string(),
+    };
+    let title = period_title(period, shown, app.stats_offset);
+    // " This Week: 12 | Average: 9.5 per week ", the average taken over every period since the first session
+    let summary = |series: &[(NaiveDate, u64)]| {
+        let value = group(series, period, shown, shown).first().map(|(_, v)| *v).unwrap_or(0);
+        let all = group(series, period, first_day, today);
+        let average = all.iter().map(|(_, v)| *v).sum::<u64>() as f64 / all.len().max(1) as f64;
+        Line::from(format!(" {}: {} | Average: {:.1} per {} ", title, value, average, period.name())).style(Style::default().fg(app.theme.blocks))
+    };
+    let max_minute_value = [
+        window(&history.daily_minutes(SessionKind::All), sideways_slots),
+        window(&history.daily_minutes(SessionKind::Focus), sideways_slots),
+        window(&history.daily_minutes(SessionKind::Break), sideways_slots),
+    ].iter().flat_map(|v| v.iter().map(|(_, m)| *m)).max().unwrap_or(0);
+    let max_y = (max_minute_value as f64 / 10.0).ceil() * 10.0;
+
     // Left: Stats categories and breakdown
     let left_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(10), Constraint::Min(5)])
+        .constraints([Constraint::Min(15), Constraint::Min(5)])
         .split(chunks[0]);
 
-    let today = Local::now().date_naive();
-    let todays_sessions = app.statistics.recent_sessions.iter().find(|(d, _)| d.date_naive() == today).map(|(_, c)| *c).unwrap_or(0);
-    let todays_minutes = app.statistics.recent_minutes.iter().find(|(d, _)| d.date_naive() == today).map(|(_, m)| *m).unwrap_or(0);
-    let todays_focus_sessions = app.statistics.recent_focus_sessions.iter().find(|(d, _)| d.date_naive() == today).map(|(_, c)| *c).unwrap_or(0);
-    let todays_focus_minutes = app.statistics.recent_focus_minutes.iter().find(|(d, _)| d.date_naive() == today).map(|(_, m)| *m).unwrap_or(0);
-    let todays_break_sessions = app.statistics.recent_break_sessions.iter().find(|(d, _)| d.date_naive() == today).map(|(_, c)| *c).unwrap_or(0);
-    let todays_break_minutes = app.statistics.recent_break_minutes.iter().find(|(d, _)| d.date_naive() == today).map(|(_, m)| *m).unwrap_or(0);
-    let todays_plants = app.statistics.recent_plants.iter().find(|(d, _)| d.date_naive() == today).map(|(_, c)| *c).unwrap_or(0);
+    let periods = History::new(app.storage.sessions_between(shown, period.end(shown)).unwrap_or_default());
+    let period_sessions = periods.total_sessions(SessionKind::All);
+    let period_minutes = periods.total_minutes(SessionKind::All);
+    let period_focus_sessions = periods.total_sessions(SessionKind::Focus);
+    let period_focus_minutes = periods.total_minutes(SessionKind::Focus);
+    let period_break_sessions = periods.total_sessions(SessionKind::Break);
+    let period_break_minutes = periods.total_minutes(SessionKind::Break);
+    let period_plants = group(&daily_plants(&app.garden.completed_plants), period, shown, shown).first().map(|(_, v)| *v).unwrap_or(0);
     let categories = vec![
-        format!("Sessions: {}", todays_sessions),
-        format!("Minutes: {}", todays_minutes),
-        format!("Focus Sessions: {}", todays_focus_sessions),
-        format!("Minutes Focused: {}", todays_focus_minutes),
-        format!("Break Sessions: {}", todays_break_sessions),
-        format!("Minutes Resting: {}", todays_break_minutes),
-        format!("Grown Plants: {}", todays_plants),
+        format!("Sessions: {}", period_sessions),
+        format!("Minutes: {}", period_minutes),
+        format!("Focus Sessions: {}", period_focus_sessions),
+        format!("Minutes Focused: {}", period_focus_minutes),
+        format!("Break Sessions: {}", period_break_sessions),
+        format!("Minutes Resting: {}", period_break_minutes),
+        format!("Grown Plants: {}", period_plants),
         format!("Current Streak: {}", app.garden.current_streak),
         format!("Longest Streak: {}", app.garden.longest_streak),
+        "Focus Heatmap".to_string(),
+        "Focus Patterns".to_string(),
+        "Goals".to_string(),
+        "Tasks & Projects".to_string(),
     ];
     let items: Vec<ListItem> = categories
         .iter()
@@ -69,59 +102,73 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
             ListItem::new(text).style(style)
         })
         .collect();
+    let list_title = if app.stats_offset == 0 { format!(" {}'s Stats ", title) } else { format!(" Stats for {} ", title) };
+    // Day | Week | Month | Year, picked with [ and ]
+    let mut selector = vec![Span::raw(" ")];
+    for (i, p) in Period::ALL.iter().enumerate() {
+        if i > 0 {
+            selector.push(Span::styled(" | ", Style::default().fg(app.theme.secondary_text)));
+        }
+        selector.push(if *p == period {
+            Span::styled(p.label(), Style::default().fg(app.theme.highlight).add_modifier(Modifier::BOLD))
+        } else {
+            Span::styled(p.label(), Style::default().fg(app.theme.secondary_text))
+        });
+    }
+    selector.push(Span::raw(" "));
     let list = List::new(items)
-        .block(Block::default().title_top(Line::from(" Today's Stats ").style(Style::default().fg(app.theme.blocks)).centered()).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)));
+        .block(Block::default().title_top(Line::from(list_title).style(Style::default().fg(app.theme.blocks)).centered()).title_bottom(Line::from(selector).centered()).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)));
     f.render_widget(list, left_chunks[0]);
 
     // Breakdown
     if app.stats_selected == 0 {
         let data = vec![
-            PieSlice::new("Focus", app.statistics.total_focus_sessions as f64, app.theme.pine),
-            PieSlice::new("Break", app.statistics.total_break_sessions as f64, app.theme.rose),
+            PieSlice::new("Focus", history.total_sessions(SessionKind::Focus) as f64, app.theme.pine),
+            PieSlice::new("Break", history.total_sessions(SessionKind::Break) as f64, app.theme.rose),
         ];
         let pie = PieChart::new(data).block(Block::default().title_top(Line::from(" Breakdown ").style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks))).pie_char(symbols::PIE_CHAR_BLOCK);
         f.render_widget(pie, left_chunks[1]);
     } else if app.stats_selected == 1 {
         let data = vec![
-            PieSlice::new("Focus", app.statistics.total_focus_minutes as f64, app.theme.pine),
-            PieSlice::new("Break", app.statistics.total_break_minutes as f64, app.theme.rose),
+            PieSlice::new("Focus", history.total_minutes(SessionKind::Focus) as f64, app.theme.pine),
+            PieSlice::new("Break", history.total_minutes(SessionKind::Break) as f64, app.theme.rose),
         ];
         let pie = PieChart::new(data).block(Block::default().title_top(Line::from(" Breakdown ").style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks))).pie_char(symbols::PIE_CHAR_BLOCK);
         f.render_widget(pie, left_chunks[1]);
     } else {
         let breakdown_content = match app.stats_selected {
             2 => {
-                let logs = app.statistics.session_log.iter().rev().filter(|l| matches!(l.session_type, crate::timer::SessionType::Focus)).take(10).map(|l| format!("Focus session - {} mins - {}", l.duration, l.end_time.format("%Y-%m-%dT%H:%M:%S%.6f%z"))).collect::<Vec<_>>().join("\n");
+                let logs = history.sessions.iter().rev().filter(|l| matches!(l.session_type, crate::timer::SessionType::Focus)).take(10).map(|l| format!("Focus session{} - {} mins - {}", outcome_suffix(l), l.duration, l.end_time.format("%Y-%m-%dT%H:%M:%S%.6f%z"))).collect::<Vec<_>>().join("\n");
                 if logs.is_empty() { "No focus sessions".to_string() } else { logs }
             }
             3 => {
-                let logs = app.statistics.session_log.iter().rev().filter(|l| matches!(l.session_type, crate::timer::SessionType::Focus)).take(10).map(|l| format!("Focus session - {} mins - {}", l.duration, l.end_time.format("%Y-%m-%dT%H:%M:%S%.6f%z"))).collect::<Vec<_>>().join("\n");
+                let logs = history.sessions.iter().rev().filter(|l| matches!(l.session_type, crate::timer::SessionType::Focus)).take(10).map(|l| format!("Focus session{} - {} mins - {}", outcome_suffix(l), l.duration, l.end_time.format("%Y-%m-%dT%H:%M:%S%.6f%z"))).collect::<Vec<_>>().join("\n");
                 if logs.is_empty() { "No focus sessions".to_string() } else { logs }
             }
             4 => {
-                let logs = app.statistics.session_log.iter().rev().filter(|l| matches!(l.session_type, crate::timer::SessionType::ShortBreak | crate::timer::SessionType::LongBreak)).take(10).map(|l| {
+                let logs = history.sessions.iter().rev().filter(|l| matches!(l.session_type, crate::timer::SessionType::ShortBreak | crate::timer::SessionType::LongBreak)).take(10).map(|l| {
                     let name = match l.session_type {
                         crate::timer::SessionType::ShortBreak => "Short break",
                         crate::timer::SessionType::LongBreak => "Long break",
                         _ => "Break",
                     };
-                    format!("{} - {} mins - {}", name, l.duration, l.end_time.format("%Y-%m-%dT%H:%M:%S%.6f%z"))
+                    format!("{}{} - {} mins - {}", name, outcome_suffix(l), l.duration, l.end_time.format("%Y-%m-%dT%H:%M:%S%.6f%z"))
                 }).collect::<Vec<_>>().join("\n");
                 if logs.is_empty() { "No break sessions".to_string() } else { logs }
             }
             5 => {
-                let logs = app.statistics.session_log.iter().rev().filter(|l| matches!(l.session_type, crate::timer::SessionType::ShortBreak | crate::timer::SessionType::LongBreak)).take(10).map(|l| {
+                let logs = history.sessions.iter().rev().filter(|l| matches!(l.session_type, crate::timer::SessionType::ShortBreak | crate::timer::SessionType::LongBreak)).take(10).map(|l| {
                     let name = match l.session_type {
                         crate::timer::SessionType::ShortBreak => "Short break",
                         crate::timer::SessionType::LongBreak => "Long break",
                         _ => "Break",
                     };
-                    format!("{} - {} mins - {}", name, l.duration, l.end_time.format("%Y-%m-%dT%H:%M:%S%.6f%z"))
+                    format!("{}{} - {} mins - {}", name, outcome_suffix(l), l.duration, l.end_time.format("%Y-%m-%dT%H:%M:%S%.6f%z"))
                 }).collect::<Vec<_>>().join("\n");
                 if logs.is_empty() { "No break sessions".to_string() } else { logs }
             }
             6 => {
-                let logs = app.garden.completed_plants.iter().rev().take(10).map(|p| format!("Grown plant - {} - {}", p.plant.stage, p.completed_at.with_timezone(&Local).format("%Y-%m-%dT%H:%M:%S%.6f%z"))).collect::<Vec<_>>().join("\n");
+                let logs = app.garden.completed_plants.iter().rev().filter(|p| !p.withered).take(10).map(|p| format!("Grown {} - {}", p.plant.species().name, p.completed_at.with_timezone(&Local).format("%Y-%m-%dT%H:%M:%S%.6f%z"))).collect::<Vec<_>>().join("\n");
                 if logs.is_empty() { "No grown plants".to_string() } else { logs }
             }
             7 => {
@@ -132,6 +179,83 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
                 let dates = app.garden.longest_streak_dates.iter().map(|d| d.format("%Y-%m-%d").to_string()).collect::<Vec<_>>().join("\n");
                 format!("Streak Dates:\n{}", dates)
             },
+            9 => {
+                // The day under the heatmap cursor
+                let day = app.heatmap_cursor;
+                let sessions = app.storage.sessions_between(day, day).unwrap_or_default();
+                let focus_minutes: u64 = sessions.iter().filter(|l| l.session_type == crate::timer::SessionType::Focus && l.outcome == crate::storage::SessionOutcome::Completed).map(|l| l.duration).sum();
+                let logs = sessions.iter().rev().map(|l| {
+                    let name = match l.session_type {
+                        crate::timer::SessionType::Focus => "Focus session",
+                        crate::timer::SessionType::ShortBreak => "Short break",
+                        crate::timer::SessionType::LongBreak => "Long break",
+                        crate::timer::SessionType::Unknown => "Session",
+                    };
+                    format!("{}{} - {} mins - {}", name, outcome_suffix(l), l.duration, l.end_time.format("%H:%M"))
+                }).collect::<Vec<_>>().join("\n");
+                let logs = if logs.is_empty() { "No sessions".to_string() } else { logs };
+                format!("{} - {} minutes focused\n\n{}", day.format("%a %d %b %Y"), focus_minutes, logs)
+            }
+            10 => {
+                let starts = match history.average_focus_starts() {
+                    Some((any, first)) => format!("Average focus start: {}, first of the day: {}", any.format("%H:%M"), first.format("%H:%M")),
+                    None => "No focus sessions yet".to_string(),
+                };
+                let total: u64 = history.focus_minutes_by_hour().iter().sum();
+                let window = match history.best_focus_window(FOCUS_WINDOW_HOURS) {
+                    Some((start, minutes)) => format!(
+                        "Best focus window: {:02}:00-{:02}:00 ({} min, {}% of focus time)",
+                        start, (start + FOCUS_WINDOW_HOURS) % 24, minutes, minutes * 100 / total.max(1)
+                    ),
+                    None => "Best focus window: not enough sessions".to_string(),
+                };
+                // Two parts of the day to a line
+                let rates = history.focus_completion_by_time_of_day().iter().map(|(part, completed, started)| match started {
+                    0 => format!("  {:<18}   - {:<12}", part, ""),
+                    _ => format!("  {:<18}{:>3}% {:<12}", part, completed * 100 / started, format!("({}/{})", completed, started)),
+                }).collect::<Vec<_>>().chunks(2).map(|pair| pair.concat()).collect::<Vec<_>>().join("\n");
+                format!("{}\n{}\nFocus sessions completed:\n{}", starts, window, rates)
+            }
+            11 => {
+                let goals = &app.settings.goals;
+                let today = Local::now().date_naive();
+                let daily = if goals.daily == 0 {
+                    "Daily goal: off".to_string()
+                } else {
+                    let (met, days) = goals.hits(&app.history, Period::Day, 30, today);
+                    format!(
+                        "Daily goal: {} {}, met on {} of the last {} days\nDays in a row: {}",
+                        goals.daily, goals.unit.name(), met, days, goals.days_in_a_row(&app.history, today)
+                    )
+                };
+                let weekly = if goals.weekly == 0 {
+                    "Weekly goal: off".to_string()
+                } else {
+                    let (met, weeks) = goals.hits(&app.history, Period::Week, 12, today);
+                    format!("Weekly goal: {} {}, met in {} of the last {} weeks", goals.weekly, goals.unit.name(), met, weeks)
+                };
+                let streaks = if goals.streak_needs_goal && goals.daily > 0 { "Streaks count days the daily goal was met" } else { "Streaks count any day with a session" };
+                format!("{}\n{}\n{}", daily, weekly, streaks)
+            }
+            12 => {
+                // Projects have their own chart, so this lists tasks, most time first
+                let spent = crate::tasks::spent_by_task(&app.history);
+                let mut tasks: Vec<_> = spent.iter().filter_map(|(id, s)| Some((app.tasks.get((*id)?)?, s))).collect();
+                tasks.sort_by_key(|(_, s)| std::cmp::Reverse(s.minutes));
+                let tasks = tasks.iter().map(|(task, s)| {
+                    let project = if task.project.is_empty() { String::new() } else { format!(" ({})", task.project) };
+                    match task.estimate {
+                        0 => format!("{}{} - {} pomodoros, {} mins", task.name, project, s.pomodoros, s.minutes),
+                        estimate => format!("{}{} - {} of {} pomodoros, {} mins", task.name, project, s.pomodoros, estimate, s.minutes),
+                    }
+                }).collect::<Vec<_>>();
+                let untracked = spent.get(&None).copied().unwrap_or_default();
+                if tasks.is_empty() {
+                    "No focus sessions on tasks yet".to_string()
+                } else {
+                    format!("Without a task - {} pomodoros, {} mins\n{}", untracked.pomodoros, untracked.minutes, tasks.join("\n"))
+                }
+            }
             _ => "Breakdown not available".to_string(),
         };
         let breakdown = Paragraph::new(breakdown_content)
@@ -144,24 +268,20 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
     match app.stats_selected {
         0 => {
             // BarChart for Sessions
-            let mut data: Vec<(DateTime<Local>, u32)> = app.statistics.recent_sessions.clone();
-            let today = Local::now();
-            if !data.iter().any(|(d, _)| d.date_naive() == today.date_naive()) {
-                data.push((today, 0));
-            }
-            data.sort_by_key(|(d, _)| d.date_naive());
-            let max_val = data.iter().map(|(_, v)| *v as u64).max().unwrap_or(0);
+            let series = history.daily_sessions(SessionKind::All);
+            let data = window(&series, upright_slots);
+            let max_val = data.iter().map(|(_, v)| *v).max().unwrap_or(0);
             let max_y = ((max_val as f64 / 10.0).ceil() * 10.0) as u64;
             let bars: Vec<Bar> = data.iter().map(|(date, value)| {
                 Bar::default()
-                    .value(*value as u64)
-                    .label(date.format(date_format).to_string().into())
+                    .value(*value)
+                    .label(label(date).into())
                     .text_value(format!("{:^5}", value))
                     .value_style(Style::default().fg(app.theme.pine).bg(app.theme.foam))
                     .style(Style::default().fg(app.theme.foam))
             }).collect();
             let barchart = BarChart::default()
-                .block(Block::default().title(Line::from(format!(" Total Sessions: {} ", app.statistics.total_sessions)).style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 0, 0)))
+                .block(Block::default().title(Line::from(format!(" Total Sessions: {} ", history.total_sessions(SessionKind::All))).style(Style::default().fg(app.theme.blocks))).title_bottom(summary(&series)).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 0, 0)))
                 .data(BarGroup::default().bars(&bars))
                 .bar_width(5)
                 .bar_gap(1)
@@ -170,18 +290,18 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
         }
         1 => {
             // Horizontal BarChart for Minutes
-            let mut data: Vec<(DateTime<Local>, u64)> = app.statistics.recent_minutes.clone();
-            data.sort_by_key(|(d, _)| *d);
+            let series = history.daily_minutes(SessionKind::All);
+            let data = window(&series, sideways_slots);
             let bars: Vec<Bar> = data.iter().map(|(date, value)| {
                 Bar::default()
                     .value(*value)
-                    .label(Line::from(date.format(date_format).to_string()))
+                    .label(Line::from(label(date)))
                     .style(Style::default().fg(app.theme.rose))
                     .text_value(format!("{:^5}", value))
                     .value_style(Style::default().fg(app.theme.love).bg(app.theme.rose))
             }).collect();
             let barchart = BarChart::default()
-                .block(Block::default().title(Line::from(format!(" Total Minutes: {} ", app.statistics.total_minutes)).style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
+                .block(Block::default().title(Line::from(format!(" Total Minutes: {} ", history.total_minutes(SessionKind::All))).style(Style::default().fg(app.theme.blocks))).title_bottom(summary(&series)).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
                 .data(BarGroup::default().bars(&bars))
                 .bar_width(1)
                 .bar_gap(0)
@@ -191,20 +311,20 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
         }
         2 => {
             // BarChart for Focus Sessions
-            let mut data: Vec<(DateTime<Local>, u64)> = app.statistics.recent_focus_sessions.iter().map(|(d, v)| (*d, *v as u64)).collect();
-            data.sort_by_key(|(d, _)| d.date_naive());
+            let series = history.daily_sessions(SessionKind::Focus);
+            let data = window(&series, upright_slots);
             let max_val = data.iter().map(|(_, v)| *v).max().unwrap_or(0);
             let max_y = ((max_val as f64 / 10.0).ceil() * 10.0) as u64;
             let bars: Vec<Bar> = data.iter().map(|(date, value)| {
                 Bar::default()
                     .value(*value)
-                    .label(date.format(date_format).to_string().into())
+                    .label(label(date).into())
                     .text_value(format!("{:^5}", value))
                     .value_style(Style::default().fg(app.theme.pine).bg(app.theme.foam))
                     .style(Style::default().fg(app.theme.foam))
             }).collect();
             let barchart = BarChart::default()
-                .block(Block::default().title(Line::from(format!(" Total Focus Sessions: {} ", app.statistics.total_focus_sessions)).style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 0, 0)))
+                .block(Block::default().title(Line::from(format!(" Total Focus Sessions: {} ", history.total_sessions(SessionKind::Focus))).style(Style::default().fg(app.theme.blocks))).title_bottom(summary(&series)).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 0, 0)))
                 .data(BarGroup::default().bars(&bars))
                 .bar_width(5)
                 .bar_gap(1)
@@ -213,18 +333,18 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
         }
         3 => {
             // Horizontal BarChart for Minutes Focused
-            let mut data: Vec<(DateTime<Local>, u64)> = app.statistics.recent_focus_minutes.clone();
-            data.sort_by_key(|(d, _)| *d);
+            let series = history.daily_minutes(SessionKind::Focus);
+            let data = window(&series, sideways_slots);
             let bars: Vec<Bar> = data.iter().map(|(date, value)| {
                 Bar::default()
                     .value(*value)
-                    .label(Line::from(date.format(date_format).to_string()))
+                    .label(Line::from(label(date)))
                     .style(Style::default().fg(app.theme.rose))
                     .text_value(format!("{:^5}", value))
                     .value_style(Style::default().fg(app.theme.love).bg(app.theme.rose))
             }).collect();
             let barchart = BarChart::default()
-                .block(Block::default().title(Line::from(format!(" Total Minutes Focused: {} ", app.statistics.total_focus_minutes)).style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
+                .block(Block::default().title(Line::from(format!(" Total Minutes Focused: {} ", history.total_minutes(SessionKind::Focus))).style(Style::default().fg(app.theme.blocks))).title_bottom(summary(&series)).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
                 .data(BarGroup::default().bars(&bars))
                 .bar_width(1)
                 .bar_gap(0)
@@ -234,20 +354,20 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
         }
         4 => {
             // BarChart for Break Sessions
-            let mut data: Vec<(DateTime<Local>, u64)> = app.statistics.recent_break_sessions.iter().map(|(d, v)| (*d, *v as u64)).collect();
-            data.sort_by_key(|(d, _)| d.date_naive());
+            let series = history.daily_sessions(SessionKind::Break);
+            let data = window(&series, upright_slots);
             let max_val = data.iter().map(|(_, v)| *v).max().unwrap_or(0);
             let max_y = ((max_val as f64 / 10.0).ceil() * 10.0) as u64;
             let bars: Vec<Bar> = data.iter().map(|(date, value)| {
                 Bar::default()
                     .value(*value)
-                    .label(date.format(date_format).to_string().into())
+                    .label(label(date).into())
                     .text_value(format!("{:^5}", value))
                     .value_style(Style::default().fg(app.theme.pine).bg(app.theme.foam))
                     .style(Style::default().fg(app.theme.foam))
             }).collect();
             let barchart = BarChart::default()
-                .block(Block::default().title(Line::from(format!(" Total Break Sessions: {} ", app.statistics.total_break_sessions)).style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 0, 0)))
+                .block(Block::default().title(Line::from(format!(" Total Break Sessions: {} ", history.total_sessions(SessionKind::Break))).style(Style::default().fg(app.theme.blocks))).title_bottom(summary(&series)).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 0, 0)))
                 .data(BarGroup::default().bars(&bars))
                 .bar_width(5)
                 .bar_gap(1)
@@ -256,18 +376,18 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
         }
         5 => {
             // Horizontal BarChart for Minutes Resting
-            let mut data: Vec<(DateTime<Local>, u64)> = app.statistics.recent_break_minutes.clone();
-            data.sort_by_key(|(d, _)| *d);
+            let series = history.daily_minutes(SessionKind::Break);
+            let data = window(&series, sideways_slots);
             let bars: Vec<Bar> = data.iter().map(|(date, value)| {
                 Bar::default()
                     .value(*value)
-                    .label(Line::from(date.format(date_format).to_string()))
+                    .label(Line::from(label(date)))
                     .style(Style::default().fg(app.theme.rose))
                     .text_value(format!("{:^5}", value))
                     .value_style(Style::default().fg(app.theme.love).bg(app.theme.rose))
             }).collect();
             let barchart = BarChart::default()
-                .block(Block::default().title(Line::from(format!(" Total Minutes Resting: {} ", app.statistics.total_break_minutes)).style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
+                .block(Block::default().title(Line::from(format!(" Total Minutes Resting: {} ", history.total_minutes(SessionKind::Break))).style(Style::default().fg(app.theme.blocks))).title_bottom(summary(&series)).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
                 .data(BarGroup::default().bars(&bars))
                 .bar_width(1)
                 .bar_gap(0)
@@ -277,20 +397,20 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
         }
         6 => {
             // BarChart for Grown Plants
-            let mut data: Vec<(DateTime<Local>, u64)> = app.statistics.recent_plants.iter().map(|(d, v)| (*d, *v as u64)).collect();
-            data.sort_by_key(|(d, _)| d.date_naive());
+            let series = daily_plants(&app.garden.completed_plants);
+            let data = window(&series, upright_slots);
             let max_val = data.iter().map(|(_, v)| *v).max().unwrap_or(0);
             let max_y = ((max_val as f64 / 10.0).ceil() * 10.0) as u64;
             let bars: Vec<Bar> = data.iter().map(|(date, value)| {
                 Bar::default()
                     .value(*value)
-                    .label(date.format(date_format).to_string().into())
+                    .label(label(date).into())
                     .text_value(format!("{:^5}", value))
                     .value_style(Style::default().fg(app.theme.vertical_value).bg(app.theme.foam))
                     .style(Style::default().fg(app.theme.foam))
             }).collect();
             let barchart = BarChart::default()
-                .block(Block::default().title(Line::from(format!(" Total Grown Plants: {} ", app.statistics.completed_plants)).style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 0, 0)))
+                .block(Block::default().title(Line::from(format!(" Total Grown Plants: {} ", app.garden.total_completed())).style(Style::default().fg(app.theme.blocks))).title_bottom(summary(&series)).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 0, 0)))
                 .data(BarGroup::default().bars(&bars))
                 .bar_width(5)
                 .bar_gap(1)
@@ -450,7 +570,190 @@ pub fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
                 areas[3],
             );
         }
+        9 => crate::ui::heatmap_ui::draw_heatmap(f, app, chunks[1]),
+        10 => draw_patterns(f, app, chunks[1]),
+        11 => draw_goals(f, app, chunks[1]),
+        12 => draw_tasks(f, app, chunks[1]),
 
         _ => {}
     }
-}
\ No newline at end of file
+}
+
+const FOCUS_WINDOW_HOURS: usize = 2;
+
+// Focus minutes by hour of the day above focus minutes by weekday, the best focus window highlighted
+fn draw_patterns(f: &mut Frame, app: &App, area: Rect) {
+    let areas = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).split(area);
+    let by_hour = app.history.focus_minutes_by_hour();
+    let window = app.history.best_focus_window(FOCUS_WINDOW_HOURS).map(|(start, _)| start);
+    let in_window = |hour: usize| window.is_some_and(|start| (hour + 24 - start) % 24 < FOCUS_WINDOW_HOURS);
+    let bars: Vec<Bar> = by_hour.iter().enumerate().map(|(hour, value)| {
+        let color = if in_window(hour) { app.theme.highlight } else { app.theme.foam };
+        Bar::default()
+            .value(*value)
+            .label(format!("{:02}", hour).into())
+            .text_value(String::new())
+            .style(Style::default().fg(color))
+    }).collect();
+    let barchart = BarChart::default()
+        .block(Block::default().title(Line::from(" Focus Minutes by Hour ").style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(2)
+        .bar_gap(1);
+    f.render_widget(barchart, areas[0]);
+
+    let by_weekday = app.history.focus_minutes_by_weekday();
+    let names = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let bars: Vec<Bar> = by_weekday.iter().zip(names).map(|(value, name)| {
+        Bar::default()
+            .value(*value)
+            .label(name.into())
+            .text_value(format!("{:^5}", value))
+            .value_style(Style::default().fg(app.theme.pine).bg(app.theme.foam))
+            .style(Style::default().fg(app.theme.foam))
+    }).collect();
+    let barchart = BarChart::default()
+        .block(Block::default().title(Line::from(" Focus Minutes by Weekday ").style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(5)
+        .bar_gap(3);
+    f.render_widget(barchart, areas[1]);
+}
+
+// Recent days against the daily goal above recent weeks against the weekly goal
+fn draw_goals(f: &mut Frame, app: &App, area: Rect) {
+    let goals = &app.settings.goals;
+    let today = Local::now().date_naive();
+    let areas = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).split(area);
+    let values = goals.daily_values(&app.history);
+    let rows = [
+        (Period::Day, goals.daily, " Daily Goal ", "No daily goal set", areas[0]),
+        (Period::Week, goals.weekly, " Weekly Goal ", "No weekly goal set", areas[1]),
+    ];
+    for (period, goal, title, unset, area) in rows {
+        let block = Block::default()
+            .title(Line::from(title).style(Style::default().fg(app.theme.blocks)))
+            .borders(Borders::ALL)
+            .style(Style::default().fg(app.theme.blocks))
+            .padding(Padding::new(1, 0, 1, 0));
+        if goal == 0 {
+            let text = Paragraph::new(format!("{}, change it in Settings", unset))
+                .style(Style::default().fg(app.theme.secondary_text))
+                .alignment(Alignment::Center)
+                .block(block);
+            f.render_widget(text, area);
+            continue;
+        }
+        let count = (block.inner(area).width / 6).max(1) as i64;
+        let from = period.shift(period.start(today), 1 - count);
+        let grouped = group(&values, period, from, today);
+        // Scaled to at least the goal, so bars short of it look short
+        let max = grouped.iter().map(|(_, v)| *v).max().unwrap_or(0).max(goal);
+        let bars: Vec<Bar> = grouped.iter().map(|(start, value)| {
+            let color = if *value >= goal { app.theme.gauge_finished } else { app.theme.foam };
+            let label = match period {
+                Period::Day => start.format("%d").to_string(),
+                _ => start.format("%d%b").to_string(),
+            };
+            Bar::default()
+                .value(*value)
+                .label(label.into())
+                .text_value(format!("{:^5}", value))
+                .value_style(Style::default().fg(app.theme.pine).bg(color))
+                .style(Style::default().fg(color))
+        }).collect();
+        let block = block.title_bottom(Line::from(format!(" Goal: {} {} ", goal, goals.unit.name())).centered());
+        let barchart = BarChart::default()
+            .block(block)
+            .data(BarGroup::default().bars(&bars))
+            .max(max)
+            .bar_width(5)
+            .bar_gap(1);
+        f.render_widget(barchart, area);
+    }
+}
+
+// Estimated against actual pomodoros for each task above focus minutes per project
+fn draw_tasks(f: &mut Frame, app: &App, area: Rect) {
+    let areas = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).split(area);
+    let spent = crate::tasks::spent_by_task(&app.history);
+    let actual = |id: u32| spent.get(&Some(id)).map(|s| s.pomodoros).unwrap_or(0);
+
+    let legend = Line::from(vec![
+        Span::styled(" ■", Style::default().fg(app.theme.foam)),
+        Span::styled(" Estimated  ", Style::default().fg(app.theme.secondary_text)),
+        Span::styled("■", Style::default().fg(app.theme.pine)),
+        Span::styled(" Actual  ", Style::default().fg(app.theme.secondary_text)),
+        Span::styled("■", Style::default().fg(app.theme.love)),
+        Span::styled(" Over estimate ", Style::default().fg(app.theme.secondary_text)),
+    ]).centered();
+    let block = Block::default()
+        .title(Line::from(" Estimated vs Actual Pomodoros ").style(Style::default().fg(app.theme.blocks)))
+        .title_bottom(legend)
+        .borders(Borders::ALL)
+        .style(Style::default().fg(app.theme.blocks))
+        .padding(Padding::new(1, 0, 1, 0));
+    // Open tasks first, then the most recently added finished ones
+    let mut tasks: Vec<&crate::tasks::Task> = app.tasks.open().collect();
+    tasks.extend(app.tasks.list.iter().rev().filter(|t| t.done));
+    tasks.truncate((block.inner(areas[0]).width / 9) as usize);
+    if tasks.is_empty() {
+        let text = Paragraph::new("No tasks yet, add one from the task picker")
+            .style(Style::default().fg(app.theme.secondary_text))
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(text, areas[0]);
+    } else {
+        let mut barchart = BarChart::default().block(block).bar_width(3).bar_gap(1).group_gap(2);
+        for task in tasks {
+            let done = actual(task.id);
+            let over = task.estimate > 0 && done > task.estimate;
+            let name: String = task.name.chars().take(7).collect();
+            let bars = [
+                Bar::default().value(task.estimate as u64).style(Style::default().fg(app.theme.foam)).value_style(Style::default().fg(app.theme.pine).bg(app.theme.foam)),
+                Bar::default().value(done as u64)
+                    .style(Style::default().fg(if over { app.theme.love } else { app.theme.pine }))
+                    .value_style(Style::default().fg(app.theme.foam).bg(if over { app.theme.love } else { app.theme.pine })),
+            ];
+            barchart = barchart.data(BarGroup::default().label(Line::from(name)).bars(&bars));
+        }
+        f.render_widget(barchart, areas[0]);
+    }
+
+    let projects = app.tasks.spent_by_project(&app.history);
+    let bars: Vec<Bar> = projects.iter().map(|(project, s)| {
+        Bar::default()
+            .value(s.minutes)
+            .label(project.chars().take(9).collect::<String>().into())
+            .text_value(format!("{:^9}", s.minutes))
+            .value_style(Style::default().fg(app.theme.pine).bg(app.theme.foam))
+            .style(Style::default().fg(app.theme.foam))
+    }).collect();
+    let barchart = BarChart::default()
+        .block(Block::default().title(Line::from(" Focus Minutes by Project ").style(Style::default().fg(app.theme.blocks))).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)).padding(Padding::new(1, 0, 1, 0)))
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(9)
+        .bar_gap(2);
+    f.render_widget(barchart, areas[1]);
+}
+
+// "Today", "This Week", ... for the current period, otherwise its dates
+fn period_title(period: Period, start: NaiveDate, offset: u32) -> String {
+    match (period, offset) {
+        (Period::Day, 0) => "Today".to_string(),
+        (Period::Week, 0) => "This Week".to_string(),
+        (Period::Month, 0) => "This Month".to_string(),
+        (Period::Year, 0) => "This Year".to_string(),
+        (Period::Day, _) => start.format("%a %d %b %Y").to_string(),
+        (Period::Week, _) => format!("Week of {}", start.format("%d %b %Y")),
+        (Period::Month, _) => start.format("%B %Y").to_string(),
+        (Period::Year, _) => start.format("%Y").to_string(),
+    }
+}
+
+fn outcome_suffix(log: &crate::storage::SessionLog) -> &'static str {
+    match log.outcome {
+        crate::storage::SessionOutcome::Completed => "",
+        crate::storage::SessionOutcome::Abandoned => " (abandoned)",
+    }
+}
@@ -1,13 +1,13 @@
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
     style::{Modifier, Style},
     text::Line,
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation},
     Frame,
 };
 use tui_big_text::{BigText, PixelSize};
 
-use crate::{app::App, timer::SessionType};
+use crate::app::App;
 
 pub fn draw_timer(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
@@ -22,14 +22,15 @@ pub fn draw_timer(f: &mut Frame, app: &mut App, area: Rect) {
         .style(Style::default().fg(app.theme.blocks));
     let inner_area = timer_block.inner(chunks[0]);
     f.render_widget(timer_block, chunks[0]);
+    app.hit_areas.timer = chunks[0];
 
     let padded = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(1), Constraint::Length(11), Constraint::Length(0)])
+        .constraints([Constraint::Length(1), Constraint::Length(14), Constraint::Length(0)])
         .split(inner_area);
     let inner = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(8), Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
+        .constraints([Constraint::Length(8), Constraint::Length(1), Constraint::Length(1), Constraint::Length(1), Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
         .split(padded[1]);
 
     // Big timer text
@@ -45,17 +46,21 @@ pub fn draw_timer(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_widget(big_text, inner[0]);
 
     // Status (session name)
-    let session_name = match app.timer.session_type {
-        crate::timer::SessionType::Focus => "Focus",
-        crate::timer::SessionType::ShortBreak => "Short Break",
-        crate::timer::SessionType::LongBreak => "Long Break",
-    };
-    let status = match app.timer.state {
+    let session_name = app.timer.session_type.name();
+    let mut status = match app.timer.state {
         crate::timer::TimerState::Idle => "Idle".to_string(),
         crate::timer::TimerState::Running => session_name.to_string(),
         crate::timer::TimerState::Paused => format!("Paused: {}", session_name),
+        crate::timer::TimerState::Suspended => format!("Suspended: {}", session_name),
         crate::timer::TimerState::Finished => "Finished".to_string(),
     };
+    if app.settings.cycle_mode && app.timer.auto_run_index.is_none() {
+        let (current, total) = app.timer.cycle_position(&app.settings);
+        status = format!("{} · Pomodoro {} of {}", status, current, total);
+    }
+    if let Some(task) = app.tasks.active_task() {
+        status = format!("{} · {}", status, task.name);
+    }
     let status_para = Paragraph::new(status)
         .style(Style::default().fg(app.theme.text))
         .alignment(ratatui::layout::Alignment::Center);
@@ -75,6 +80,29 @@ pub fn draw_timer(f: &mut Frame, app: &mut App, area: Rect) {
         .label(format!("{}%", (app.timer.progress() * 100.0) as u16));
     f.render_widget(gauge, inner[2]);
 
+    // Goal gauges, only for goals that are set
+    let today = chrono::Local::now().date_naive();
+    let goals = &app.settings.goals;
+    let goal_rows = [
+        ("Today", goals.daily, today),
+        ("This week", goals.weekly, crate::history::Period::Week.start(today)),
+    ];
+    let mut row = 4;
+    for (label, goal, from) in goal_rows {
+        if goal == 0 {
+            continue;
+        }
+        let done = goals.progress(&app.history, from, today);
+        let color = if done >= goal { app.theme.gauge_finished } else { app.theme.highlight };
+        let [goal_area] = Layout::horizontal([Constraint::Length(44)]).flex(Flex::Center).areas(inner[row]);
+        let goal_gauge = Gauge::default()
+            .gauge_style(Style::default().fg(color))
+            .ratio((done as f64 / goal as f64).min(1.0))
+            .label(format!("{}: {}/{} {}", label, done, goal, goals.unit.name()));
+        f.render_widget(goal_gauge, goal_area);
+        row += 1;
+    }
+
     // Bottom dual blocks
     let bottom_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -101,8 +129,9 @@ pub fn draw_timer(f: &mut Frame, app: &mut App, area: Rect) {
             ListItem::new(text).style(style)
         })
         .collect();
-    let session_list = List::new(session_items)
-        .block(Block::default().title_top(Line::from(" Sessions ").style(Style::default().fg(app.theme.blocks)).centered()).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks)));
+    let session_block = Block::default().title_top(Line::from(" Sessions ").style(Style::default().fg(app.theme.blocks)).centered()).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks));
+    app.hit_areas.sessions = session_block.inner(left_chunks[0]);
+    let session_list = List::new(session_items).block(session_block);
     f.render_widget(session_list, left_chunks[0]);
     let add_legend = Paragraph::new("Add to auto [Enter]")
         .style(Style::default().fg(app.theme.secondary_text))
@@ -117,15 +146,12 @@ pub fn draw_timer(f: &mut Frame, app: &mut App, area: Rect) {
     let auto_block = Block::default().title_top(Line::from(" Auto-Run Set ").style(Style::default().fg(app.theme.blocks)).centered()).borders(Borders::ALL).style(Style::default().fg(app.theme.blocks));
     let inner_area = auto_block.inner(right_chunks[0]);
     f.render_widget(auto_block, right_chunks[0]);
+    app.hit_areas.auto_run = inner_area;
     let auto_items: Vec<ListItem> = app.timer.auto_run
         .iter()
         .enumerate()
         .map(|(i, s)| {
-            let name = match s {
-                SessionType::Focus => "Focus",
-                SessionType::ShortBreak => "Short Break",
-                SessionType::LongBreak => "Long Break",
-            };
+            let name = s.name();
             let is_selected = i == app.timer_selected_auto && app.focus == crate::app::Focus::Right;
             let text = if is_selected { format!("→ {}", name) } else { format!("  {}", name) };
             let style = if is_selected {
@@ -160,4 +186,31 @@ pub fn draw_timer(f: &mut Frame, app: &mut App, area: Rect) {
         .end_style(Style::default().fg(app.theme.secondary_text));
     f.render_stateful_widget(scrollbar, scrollbar_area, &mut app.timer_auto_scrollbar_state);
 
-}
\ No newline at end of file
+}
+
+pub fn draw_suspend_prompt(f: &mut Frame, app: &App, area: Rect) {
+    let gap = app.timer.suspend_gap.unwrap_or_default();
+    let gap_text = if gap.num_hours() > 0 {
+        format!("{}h {:02}m", gap.num_hours(), gap.num_minutes() % 60)
+    } else {
+        format!("{}m {:02}s", gap.num_minutes(), gap.num_seconds() % 60)
+    };
+    let [popup] = Layout::vertical([Constraint::Length(7)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::horizontal([Constraint::Length(48)]).flex(Flex::Center).areas(popup);
+    let block = Block::default()
+        .title_top(Line::from(" Welcome Back ").style(Style::default().fg(app.theme.blocks)).centered())
+        .borders(Borders::ALL)
+        .style(Style::default().fg(app.theme.blocks));
+    let text = vec![
+        Line::from(format!("The computer slept for {}", gap_text)),
+        Line::from(""),
+        Line::from("Count it [C] | Discard it [D] | End session [E]")
+            .style(Style::default().fg(app.theme.highlight)),
+    ];
+    let para = Paragraph::new(text)
+        .block(block)
+        .style(Style::default().fg(app.theme.text))
+        .alignment(Alignment::Center);
+    f.render_widget(Clear, popup);
+    f.render_widget(para, popup);
+}